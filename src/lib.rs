@@ -5,14 +5,114 @@
 //! (by calling the `next()` method until no further result is returned).
 //!
 //! This also exports several range utilities for use with strings.
+//!
+//! The commonly-used items can be imported at once with `use query_range::prelude::*;`.
+
+#![cfg_attr(test, allow(
+    clippy::while_let_on_iterator,
+    clippy::redundant_closure,
+    clippy::bool_assert_comparison,
+))]
 
 // Public exports -------------------------------------------------------------------------------- /
 
 
 pub use range::query_range_iterator::QueryRangeItr;
+pub use range::byte_query_range_iterator::ByteQueryRangeItr;
+pub use range::predicate_range_iterator::PredicateRangeItr;
+pub use range::stats_iterator::{StatsIter, MatchStats};
+pub use range::snippet::Snippet;
+pub use range::edit::Edit;
+pub use range::query_match::Match;
+pub use range::segment::Segment;
+pub use range::range_error::RangeError;
+pub use range::query_range_error::QueryRangeError;
+pub use range::tie_break::TieBreak;
 pub use range::utility;
-pub use range::utility::{to_title_case, get_range, Shift, shift_range, shift_range_in_content, is_within};
+pub use range::utility::{
+    to_title_case, to_title_case_words, to_title_case_preserve_acronyms, toggle_case,
+    to_snake_case, to_kebab_case, to_camel_case, get_range, get_last_range,
+    get_all_ranges, get_byte_range, search_bytes, search_lines, search_char_stream, nearest_match,
+    most_centered_match, min_window, after_last, before_first,
+    relevance, apply_replacements, reassemble, replace_many, repeat_matcher,
+    find_char_ranges, is_tiling, tiling_distance,
+    Shift, shift_range, shift_range_bounds, shift_range_saturating, shift_range_in_content,
+    shift_ranges_after, is_within, is_within_strict, is_match_at, trim_range,
+    snap_boundary_down, snap_boundary_up, validate_ranges, merge_ranges,
+};
+#[cfg(feature = "regex")]
+pub use range::utility::replace_all_regex;
 
 // Modules --------------------------------------------------------------------------------------- /
 
 mod range;
+pub mod prelude;
+
+// Tests ----------------------------------------------------------------------------------------- /
+
+/// Checks that an empty query is consistently treated as "no match" by every entry point.
+#[cfg(test)]
+mod empty_query_tests {
+    use super::*;
+
+    #[test]
+    fn get_range_finds_nothing() {
+        assert_eq!(get_range("", "haystack"), None);
+        assert_eq!(get_range("", ""), None);
+        assert_eq!(get_last_range("", "haystack"), None);
+    }
+
+    #[test]
+    fn get_all_ranges_finds_nothing() {
+        assert!(get_all_ranges("", "haystack").is_empty());
+    }
+
+    #[test]
+    fn iterator_yields_nothing() {
+        let ranges: Vec<_> = QueryRangeItr::new("", "haystack").collect();
+        assert!(ranges.is_empty());
+        let ranges: Vec<_> = QueryRangeItr::new("", "").collect();
+        assert!(ranges.is_empty());
+    }
+
+    #[test]
+    fn iterator_terminates_immediately() {
+        assert_eq!(QueryRangeItr::new("", "abc").count(), 0);
+        assert_eq!(QueryRangeItr::new("", "abc").next(), None);
+    }
+
+    #[test]
+    fn every_matching_mode_terminates() {
+        assert_eq!(QueryRangeItr::new_overlapping("", "abc").count(), 0);
+        assert_eq!(QueryRangeItr::new_case_insensitive("", "abc").count(), 0);
+        assert_eq!(QueryRangeItr::new_tolerating("", "abc", '-').count(), 0);
+        assert_eq!(QueryRangeItr::new_flexible_ws("", "abc").count(), 0);
+        assert_eq!(QueryRangeItr::new_with_stride("", "abc", 0).count(), 0);
+        assert_eq!(QueryRangeItr::new_inverted_case_insensitive("", "abc").count(), 1);
+    }
+
+    #[test]
+    fn inverted_iterator_yields_whole_content() {
+        let ranges: Vec<_> = QueryRangeItr::new_inverted("", "haystack").collect();
+        assert_eq!(ranges, vec![0..8]);
+    }
+
+    #[test]
+    fn contains_is_false() {
+        assert!(!QueryRangeItr::contains("", "haystack"));
+        assert!(!QueryRangeItr::contains_case_insensitive("", "haystack"));
+        assert!(!QueryRangeItr::contains("", ""));
+    }
+
+    #[test]
+    fn collect_strings_is_empty() {
+        assert!(QueryRangeItr::new("", "haystack").collect_strings().is_empty());
+    }
+
+    #[test]
+    fn transforms_leave_content_unchanged() {
+        let content = "haystack";
+        assert_eq!(QueryRangeItr::transform_query("", content, |it| it.to_uppercase()), "haystack");
+        assert_eq!(QueryRangeItr::transform_other("", content, |it| it.to_uppercase()), "HAYSTACK");
+    }
+}
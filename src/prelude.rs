@@ -0,0 +1,38 @@
+//! # Prelude
+//!
+//! Re-exports every public item of this package, so they can all be imported at once:
+//!
+//! ```
+//! use query_range::prelude::*;
+//!
+//! let content = "haystackneedlehaystack";
+//! let range = QueryRangeItr::new("needle", content).next().unwrap();
+//! assert_eq!(Some(range.clone()), get_range("needle", content));
+//! assert!(is_within(content, &range));
+//! assert_eq!(shift_range(range, Shift::Up(2)), Some(10..16));
+//! ```
+
+pub use crate::range::query_range_iterator::QueryRangeItr;
+pub use crate::range::byte_query_range_iterator::ByteQueryRangeItr;
+pub use crate::range::predicate_range_iterator::PredicateRangeItr;
+pub use crate::range::stats_iterator::{StatsIter, MatchStats};
+pub use crate::range::snippet::Snippet;
+pub use crate::range::edit::Edit;
+pub use crate::range::query_match::Match;
+pub use crate::range::segment::Segment;
+pub use crate::range::range_error::RangeError;
+pub use crate::range::query_range_error::QueryRangeError;
+pub use crate::range::tie_break::TieBreak;
+pub use crate::range::utility::{
+    to_title_case, to_title_case_words, to_title_case_preserve_acronyms, toggle_case,
+    to_snake_case, to_kebab_case, to_camel_case, get_range, get_last_range,
+    get_all_ranges, get_byte_range, search_bytes, search_lines, search_char_stream, nearest_match,
+    most_centered_match, min_window, after_last, before_first,
+    relevance, apply_replacements, reassemble, replace_many, repeat_matcher,
+    find_char_ranges, is_tiling, tiling_distance,
+    Shift, shift_range, shift_range_bounds, shift_range_saturating, shift_range_in_content,
+    shift_ranges_after, is_within, is_within_strict, is_match_at, trim_range,
+    snap_boundary_down, snap_boundary_up, validate_ranges, merge_ranges,
+};
+#[cfg(feature = "regex")]
+pub use crate::range::utility::replace_all_regex;
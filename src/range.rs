@@ -1,2 +1,12 @@
 pub mod query_range_iterator;
 pub mod utility;
+pub mod stats_iterator;
+pub mod snippet;
+pub mod edit;
+pub mod segment;
+pub mod range_error;
+pub mod tie_break;
+pub mod byte_query_range_iterator;
+pub mod predicate_range_iterator;
+pub mod query_match;
+pub mod query_range_error;
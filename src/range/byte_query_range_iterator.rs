@@ -0,0 +1,105 @@
+use std::ops::Range;
+use super::utility::get_byte_range;
+
+/// Iterates all found byte query within given byte content, such as binary log frames, without
+/// requiring either to be valid UTF-8. Like `QueryRangeItr`, an empty query never matches.
+///
+/// ### Example
+/// ```
+/// use query_range::ByteQueryRangeItr;
+///
+/// let content = b"frame\x00\x01frame\x00\x01";
+/// let ranges: Vec<_> = ByteQueryRangeItr::new(b"\x00\x01", content).collect();
+/// assert_eq!(ranges, vec![5..7, 12..14]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ByteQueryRangeItr<'a> {
+    inverted: bool,
+    query: &'a [u8],
+    content: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> ByteQueryRangeItr<'a> {
+
+    /// Creates a new iterator with given content or query which will iterate each *found* instance
+    /// of the query.
+    pub fn new(query: &'a [u8], content: &'a [u8]) -> ByteQueryRangeItr<'a> {
+        Self { inverted: false, query, content, offset: 0 }
+    }
+
+    /// Creates a new iterator with given content or query which will iterate the content in
+    /// between each *found* instance of the query.
+    pub fn new_inverted(query: &'a [u8], content: &'a [u8]) -> ByteQueryRangeItr<'a> {
+        Self { inverted: true, ..Self::new(query, content) }
+    }
+
+    /// Private, finds the next match of the query at or after the current offset, as a range into
+    /// the full content.
+    fn find_next(&self) -> Option<Range<usize>> {
+        get_byte_range(self.query, &self.content[self.offset..])
+            .map(|range| (range.start + self.offset)..(range.end + self.offset))
+    }
+
+    /// Gets the next range that matches the given query.
+    fn next_standard(&mut self) -> Option<Range<usize>> {
+        let range = self.find_next()?;
+        self.offset = range.end;
+        Some(range)
+    }
+
+    /// Gets the next range that doesn't match the given query.
+    fn next_inverted(&mut self) -> Option<Range<usize>> {
+        let len = self.content.len();
+        if self.offset >= len {
+            return None;
+        }
+        let found = self.find_next().unwrap_or(len..len);
+        let range = self.offset..found.start;
+        self.offset = found.end;
+        Some(range)
+    }
+}
+
+// Iterator implementation ----------------------------------------------------------------------- /
+
+impl<'a> Iterator for ByteQueryRangeItr<'a> {
+    type Item = Range<usize>;
+
+    /// Gets next range of the query in the content.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.inverted {
+            self.next_inverted()
+        } else {
+            self.next_standard()
+        }
+    }
+}
+
+// Tests ----------------------------------------------------------------------------------------- /
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_find_bytes_in_buffer() {
+        let content: &[u8] = &[0xff, 0x00, 0x01, 0x00, 0x00, 0x01, 0xfe];
+        let ranges: Vec<Range<usize>> = ByteQueryRangeItr::new(b"\x00\x01", content).collect();
+        assert_eq!(ranges, vec![1..3, 4..6]);
+    }
+
+    #[test]
+    fn yields_nothing_without_match() {
+        let content: &[u8] = &[0xff, 0x01, 0x00];
+        assert_eq!(ByteQueryRangeItr::new(b"\x00\x01", content).next(), None);
+        assert_eq!(ByteQueryRangeItr::new(b"", content).next(), None);
+    }
+
+    #[test]
+    fn can_invert_byte_matches() {
+        let content: &[u8] = b"ab\x00cd\x00";
+        let ranges: Vec<Range<usize>> = ByteQueryRangeItr::new_inverted(b"\x00", content).collect();
+        assert_eq!(ranges, vec![0..2, 3..5]);
+    }
+}
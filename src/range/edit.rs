@@ -0,0 +1,10 @@
+use std::ops::Range;
+
+/// A single edit to content: replace the content at `range` with `insert`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    /// The byte range of the content to replace.
+    pub range: Range<usize>,
+    /// The text to insert in place of the range.
+    pub insert: String,
+}
@@ -0,0 +1,97 @@
+use std::ops::Range;
+
+/// Iterates each maximal run of consecutive chars in given content that satisfy a predicate, such
+/// as all digit sequences. Created by `QueryRangeItr::matching` or
+/// `QueryRangeItr::matching_inverted`.
+///
+/// ### Example
+/// ```
+/// use query_range::QueryRangeItr;
+///
+/// let ranges: Vec<_> = QueryRangeItr::matching("abc123def45", |c| c.is_ascii_digit()).collect();
+/// assert_eq!(ranges, vec![3..6, 9..11]);
+/// ```
+pub struct PredicateRangeItr<'a, F> where F: Fn(char) -> bool {
+    inverted: bool,
+    predicate: F,
+    content: &'a str,
+    offset: usize,
+}
+
+impl<'a, F> PredicateRangeItr<'a, F> where F: Fn(char) -> bool {
+
+    /// Creates a new iterator over runs of chars satisfying the predicate or, when inverted, runs
+    /// of chars that don't.
+    pub fn new(content: &'a str, predicate: F, inverted: bool) -> PredicateRangeItr<'a, F> {
+        Self { inverted, predicate, content, offset: 0 }
+    }
+
+    /// Private, gets the byte offset of the first char at or after the given offset for which the
+    /// predicate (after inversion) is the given value, or the content length if there's none.
+    fn find_from(&self, offset: usize, in_run: bool) -> usize {
+        self.content[offset..]
+            .char_indices()
+            .find(|(_, character)| ((self.predicate)(*character) != self.inverted) == in_run)
+            .map_or(self.content.len(), |(index, _)| offset + index)
+    }
+}
+
+// Iterator implementation ----------------------------------------------------------------------- /
+
+impl<'a, F> Iterator for PredicateRangeItr<'a, F> where F: Fn(char) -> bool {
+    type Item = Range<usize>;
+
+    /// Gets next run of chars satisfying the predicate.
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.find_from(self.offset, true);
+        if start >= self.content.len() {
+            self.offset = self.content.len();
+            return None;
+        }
+        let end = self.find_from(start, false);
+        self.offset = end;
+        Some(start..end)
+    }
+}
+
+// Tests ----------------------------------------------------------------------------------------- /
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_find_digit_runs() {
+        let ranges: Vec<Range<usize>> = PredicateRangeItr::new("abc123def45", |c| c.is_ascii_digit(), false).collect();
+        assert_eq!(ranges, vec![3..6, 9..11]);
+    }
+
+    #[test]
+    fn can_find_whitespace_runs() {
+        let ranges: Vec<Range<usize>> = PredicateRangeItr::new("a  b\t\nc ", char::is_whitespace, false).collect();
+        assert_eq!(ranges, vec![1..3, 4..6, 7..8]);
+    }
+
+    #[test]
+    fn can_find_inverted_runs() {
+        let ranges: Vec<Range<usize>> = PredicateRangeItr::new("abc123def45", |c| c.is_ascii_digit(), true).collect();
+        assert_eq!(ranges, vec![0..3, 6..9]);
+    }
+
+    #[test]
+    fn entirely_matching_content_is_a_single_run() {
+        let ranges: Vec<Range<usize>> = PredicateRangeItr::new("12345", |c| c.is_ascii_digit(), false).collect();
+        assert_eq!(ranges, vec![0..5]);
+        let ranges: Vec<Range<usize>> = PredicateRangeItr::new("12345", |c| c.is_ascii_digit(), true).collect();
+        assert!(ranges.is_empty());
+    }
+
+    #[test]
+    fn entirely_non_matching_content_has_no_runs() {
+        let ranges: Vec<Range<usize>> = PredicateRangeItr::new("abcdé", |c| c.is_ascii_digit(), false).collect();
+        assert!(ranges.is_empty());
+        let ranges: Vec<Range<usize>> = PredicateRangeItr::new("abcdé", |c| c.is_ascii_digit(), true).collect();
+        assert_eq!(ranges, vec![0..6]);
+        assert_eq!(PredicateRangeItr::new("", |c| c.is_ascii_digit(), false).next(), None);
+    }
+}
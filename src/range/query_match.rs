@@ -0,0 +1,31 @@
+use std::ops::Range;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A match of the query, owning the matched text along with its range, such as for sending match
+/// results elsewhere. With the `serde` feature, it serializes as
+/// `{"range":{"start":...,"end":...},"text":...}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Match {
+    /// The byte range of the match in the content.
+    pub range: Range<usize>,
+    /// The matched text.
+    pub text: String,
+}
+
+// Tests ----------------------------------------------------------------------------------------- /
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_round_trips_through_json() {
+        let original = Match { range: 8..14, text: String::from("needle") };
+        let json = serde_json::to_string(&original).unwrap();
+        assert_eq!(json, r#"{"range":{"start":8,"end":14},"text":"needle"}"#);
+        let parsed: Match = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, original);
+    }
+}
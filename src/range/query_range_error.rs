@@ -0,0 +1,22 @@
+use std::error::Error;
+use std::fmt;
+
+/// An error for a query that can't be searched for in the content, from `QueryRangeItr::try_new`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryRangeError {
+    /// The query is empty, so it would never match.
+    EmptyQuery,
+    /// The query is longer (in bytes) than the content, so it could never be found.
+    QueryLongerThanContent,
+}
+
+impl fmt::Display for QueryRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryRangeError::EmptyQuery => write!(f, "query is empty"),
+            QueryRangeError::QueryLongerThanContent => write!(f, "query is longer than the content"),
+        }
+    }
+}
+
+impl Error for QueryRangeError {}
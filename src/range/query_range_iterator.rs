@@ -1,6 +1,26 @@
 use std::ops::Range;
-use std::cmp::min;
-use super::utility::{ get_range, Shift, shift_range, shift_range_in_content, is_within };
+use std::cmp::{min, Reverse};
+use std::convert::TryFrom;
+use std::num::TryFromIntError;
+use num::PrimInt;
+use std::sync::mpsc::{Sender, SendError};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::iter::FusedIterator;
+use std::borrow::Cow;
+use super::utility::{
+    get_range, get_last_range, get_range_flexible_ws, get_range_ignore_ascii_case, get_range_tolerating,
+    Shift, shift_range, shift_range_in_content, is_within, is_whole_word, is_word_boundary_after,
+    snap_boundary_down, snap_boundary_up, escape_html, merge_ranges, expand_by_chars, edit_distance, title_case_word,
+    apply_replacements, glob_match_len, overlaps_itself,
+};
+use super::stats_iterator::StatsIter;
+use super::snippet::Snippet;
+use super::edit::Edit;
+use super::query_match::Match;
+use super::query_range_error::QueryRangeError;
+use super::segment::Segment;
+use super::tie_break::TieBreak;
+use super::predicate_range_iterator::PredicateRangeItr;
 
 /// Iterates all found query within given content.
 ///
@@ -60,8 +80,20 @@ use super::utility::{ get_range, Shift, shift_range, shift_range_in_content, is_
 ///     |it| to_title_case(it), // non-query transform
 /// );
 /// ```
+#[derive(Debug, Clone)]
 pub struct QueryRangeItr<'a> {
     inverted: bool,
+    word_suffix: bool,
+    whole_word: bool,
+    flexible_ws: bool,
+    case_insensitive: bool,
+    overlapping: bool,
+    escape: Option<char>,
+    stride: usize,
+    tolerate: Option<char>,
+    limit: Option<usize>,
+    window: Option<VecDeque<Range<usize>>>,
+    peeked: Option<Range<usize>>,
     query: &'a str,
     current_content: &'a str,
     full_content: &'a str,
@@ -77,6 +109,17 @@ impl<'a> QueryRangeItr<'a> {
     fn new_base(query: &'a str, content: &'a str, inverted: bool) -> QueryRangeItr<'a> {
         Self {
             inverted,
+            word_suffix: false,
+            whole_word: false,
+            flexible_ws: false,
+            case_insensitive: false,
+            overlapping: false,
+            escape: None,
+            stride: 0,
+            tolerate: None,
+            limit: None,
+            window: None,
+            peeked: None,
             query,
             current_content: content,
             full_content: content,
@@ -85,30 +128,517 @@ impl<'a> QueryRangeItr<'a> {
     }
 
     /// Creates a new iterator with given content or query which will iterate each *found* instance
-    /// of the query.
+    /// of the query. An empty query never matches, so the iterator yields nothing.
     pub fn new(query: &'a str, content: &'a str) -> QueryRangeItr<'a> {
         Self::new_base(query, content, false)
     }
 
+    /// Creates a new iterator like `new`, but returns an error for a query that could never match,
+    /// so that a bogus query can be told apart from one that was searched for and not found.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::{QueryRangeItr, QueryRangeError};
+    ///
+    /// assert!(QueryRangeItr::try_new("needle", "haystack").is_ok());
+    /// assert_eq!(QueryRangeItr::try_new("", "haystack").err(), Some(QueryRangeError::EmptyQuery));
+    /// ```
+    pub fn try_new(query: &'a str, content: &'a str) -> Result<QueryRangeItr<'a>, QueryRangeError> {
+        if query.is_empty() {
+            Err(QueryRangeError::EmptyQuery)
+        } else if query.len() > content.len() {
+            Err(QueryRangeError::QueryLongerThanContent)
+        } else {
+            Ok(Self::new(query, content))
+        }
+    }
+
     /// Creates a new iterator with given content or query which will iterate the content in
-    /// between each *found* instance of the query.
+    /// between each *found* instance of the query. An empty query never matches, so the whole
+    /// content is yielded as a single range.
     pub fn new_inverted(query: &'a str, content: &'a str) -> QueryRangeItr<'a> {
         Self::new_base(query, content, true)
     }
 
+    /// Creates a new iterator like `new`, but ignoring ASCII case, so `"Needle"` also matches
+    /// `"NEEDLE"`. Only ASCII letters are compared insensitively (other characters must match
+    /// exactly), so ranges always line up with the original content and `&content[range]` yields
+    /// the original casing.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let content = "haystackNEEDLEhaystack";
+    /// let strings = QueryRangeItr::new_case_insensitive("Needle", content).collect_strings();
+    /// assert_eq!(strings, vec!["NEEDLE"]);
+    /// ```
+    pub fn new_case_insensitive(query: &'a str, content: &'a str) -> QueryRangeItr<'a> {
+        Self { case_insensitive: true, ..Self::new(query, content) }
+    }
+
+    /// Creates a new iterator like `new_inverted`, but ignoring ASCII case when finding the query,
+    /// as with `new_case_insensitive`.
+    pub fn new_inverted_case_insensitive(query: &'a str, content: &'a str) -> QueryRangeItr<'a> {
+        Self { case_insensitive: true, ..Self::new_inverted(query, content) }
+    }
+
+    /// Creates a new iterator over each maximal run of consecutive chars satisfying the predicate,
+    /// for matching without a literal query, such as all digit sequences.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let content = "abc123def45";
+    /// let strings: Vec<_> = QueryRangeItr::matching(content, |c| c.is_ascii_digit())
+    ///     .map(|range| &content[range])
+    ///     .collect();
+    /// assert_eq!(strings, vec!["123", "45"]);
+    /// ```
+    pub fn matching<F>(content: &'a str, predicate: F) -> PredicateRangeItr<'a, F> where F: Fn(char) -> bool {
+        PredicateRangeItr::new(content, predicate, false)
+    }
+
+    /// Creates a new iterator over each maximal run of consecutive chars *not* satisfying the
+    /// predicate, as the inverse of `matching`.
+    pub fn matching_inverted<F>(content: &'a str, predicate: F) -> PredicateRangeItr<'a, F> where F: Fn(char) -> bool {
+        PredicateRangeItr::new(content, predicate, true)
+    }
+
+    /// Creates a new iterator like `new`, but trims leading and trailing whitespace from the query
+    /// first, so an accidentally padded query such as `" needle "` still finds `"needle"`.
+    pub fn new_trimmed(query: &'a str, content: &'a str) -> QueryRangeItr<'a> {
+        Self::new(query.trim(), content)
+    }
+
+    /// Creates a new iterator which will only iterate instances of the query that end a word, such
+    /// as word endings or file extensions: the match must be followed by a word boundary
+    /// (whitespace, punctuation or the end of the content). For example, `"ing"` is found in
+    /// `"running"` and `"sing"`, but not in `"ingest"`.
+    pub fn new_word_suffix(query: &'a str, content: &'a str) -> QueryRangeItr<'a> {
+        Self { word_suffix: true, ..Self::new(query, content) }
+    }
+
+    /// Creates a new iterator which will only iterate instances of the query that are whole words:
+    /// the chars just before and after the match must not be word characters (alphanumeric,
+    /// including accented letters, or `_`), or the match must be at the edge of the content. For
+    /// example, `"cat"` is found in `"a cat!"`, but not in `"category"` or `"café"`.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let ranges: Vec<_> = QueryRangeItr::new_whole_word("cat", "category concatenate cat").collect();
+    /// assert_eq!(ranges, vec![21..24]);
+    /// ```
+    pub fn new_whole_word(query: &'a str, content: &'a str) -> QueryRangeItr<'a> {
+        Self { whole_word: true, ..Self::new(query, content) }
+    }
+
+    /// Creates a new iterator which ignores instances of the query that are escaped, meaning they
+    /// are immediately preceded by the escape character. An escaped escape character doesn't
+    /// escape what follows, so with `\` as the escape, `,` is found in `a,b` and `a\\,b`, but
+    /// not in `a\,b`.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let ranges: Vec<_> = QueryRangeItr::new_with_escape(",", "a,b\\,c", '\\').collect();
+    /// assert_eq!(ranges, vec![1..2]);
+    /// ```
+    pub fn new_with_escape(query: &'a str, content: &'a str, escape: char) -> QueryRangeItr<'a> {
+        Self { escape: Some(escape), ..Self::new(query, content) }
+    }
+
+    /// Creates a new iterator where any run of whitespace in the query matches any run of
+    /// whitespace in the content, such as when searching natural-language phrases. So `"hello
+    /// world"` also matches `"hello\n  world"`. Each range covers the actual content matched, which
+    /// may differ in length from the query. Leading and trailing whitespace in the query is ignored.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let content = "say hello\n  world";
+    /// let ranges: Vec<_> = QueryRangeItr::new_flexible_ws("hello world", content).collect();
+    /// assert_eq!(ranges, vec![4..17]);
+    /// ```
+    pub fn new_flexible_ws(query: &'a str, content: &'a str) -> QueryRangeItr<'a> {
+        Self { flexible_ws: true, ..Self::new(query, content) }
+    }
+
+    /// Creates a new iterator which also matches the query with a single `optional` char inserted
+    /// between any pair of query chars, such as for hyphenated words. So `"email"` matches both
+    /// `"email"` and `"e-mail"`. Each range covers the actual content matched.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let ranges: Vec<_> = QueryRangeItr::new_tolerating("email", "e-mail or email", '-').collect();
+    /// assert_eq!(ranges, vec![0..6, 10..15]);
+    /// ```
+    pub fn new_tolerating(query: &'a str, content: &'a str, optional: char) -> QueryRangeItr<'a> {
+        Self { tolerate: Some(optional), ..Self::new(query, content) }
+    }
+
+    /// Creates a new iterator which also finds overlapping instances of the query: after each
+    /// match, the search resumes one char past the start of the match rather than at its end. So
+    /// `"aa"` is found 3 times in `"aaaa"`, rather than twice.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let ranges: Vec<_> = QueryRangeItr::new_overlapping("aa", "aaaa").collect();
+    /// assert_eq!(ranges, vec![0..2, 1..3, 2..4]);
+    /// ```
+    pub fn new_overlapping(query: &'a str, content: &'a str) -> QueryRangeItr<'a> {
+        Self { overlapping: true, ..Self::new(query, content) }
+    }
+
+    /// Creates a new iterator which, after each match, advances by at least `stride` bytes from the
+    /// match start (or past the match end, if that's further), snapped up to a char boundary. Any
+    /// matches starting within the stride are skipped, thinning out dense matches.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let ranges: Vec<_> = QueryRangeItr::new_with_stride("a", "aaaaaa", 4).collect();
+    /// assert_eq!(ranges, vec![0..1, 4..5]);
+    /// ```
+    pub fn new_with_stride(query: &'a str, content: &'a str, stride: usize) -> QueryRangeItr<'a> {
+        Self { stride, ..Self::new(query, content) }
+    }
+
+    /// Limits the iterator to yielding at most `k` ranges. Once they have been yielded, the
+    /// iterator stops without scanning the rest of the content. Iterating from the back yields the
+    /// same first `k` ranges in reverse, so the first call to `next_back` collects them up front.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let ranges: Vec<_> = QueryRangeItr::new("a", "aaaa").limit(2).collect();
+    /// assert_eq!(ranges, vec![0..1, 1..2]);
+    /// ```
+    pub fn limit(self, k: usize) -> QueryRangeItr<'a> {
+        Self { limit: Some(k), ..self }
+    }
+
+    /// Private, counts a yielded range against the limit, if there is one.
+    fn count_against_limit(&mut self) {
+        if let Some(remaining) = self.limit.as_mut() {
+            *remaining -= 1;
+        }
+    }
+
+    /// Gets the next range without consuming it, so the following call to `next` returns the same
+    /// range, such as for a tokenizer deciding whether to consume a match.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let mut itr = QueryRangeItr::new("ab", "xabab");
+    /// assert_eq!(itr.peek_next(), Some(1..3));
+    /// assert_eq!(itr.next(), Some(1..3));
+    /// assert_eq!(itr.next(), Some(3..5));
+    /// ```
+    pub fn peek_next(&mut self) -> Option<Range<usize>> {
+        if self.peeked.is_none() {
+            self.peeked = self.next();
+        }
+        self.peeked.clone()
+    }
+
     /// Collects all iterated ranges and builds an array of strings from the original content at those ranges
     pub fn collect_strings(&mut self) -> Vec<String> {
         let content = self.full_content;
         self.map(|range| String::from(&content[range])).collect()
     }
 
+    /// Collects all iterated ranges as strings from the original content, like `collect_strings`,
+    /// sorted lexicographically.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let sorted = QueryRangeItr::new_case_insensitive("b", "b B b").collect_sorted_strings();
+    /// assert_eq!(sorted, vec!["B", "b", "b"]);
+    /// ```
+    pub fn collect_sorted_strings(&mut self) -> Vec<String> {
+        let mut strings = self.collect_strings();
+        strings.sort_unstable();
+        strings
+    }
+
+    /// Collects all iterated ranges, each paired with the matched text as a `Match`. With the
+    /// `serde` feature, the matches can be serialized, such as for sending to a frontend as JSON.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::{QueryRangeItr, Match};
+    ///
+    /// let matches = QueryRangeItr::new("needle", "haystackneedle").collect_matches();
+    /// assert_eq!(matches, vec![Match { range: 8..14, text: String::from("needle") }]);
+    /// ```
+    pub fn collect_matches(&mut self) -> Vec<Match> {
+        let content = self.full_content;
+        self.map(|range| Match { text: String::from(&content[range.clone()]), range }).collect()
+    }
+
+    /// Collects all iterated ranges converted to char indices rather than byte offsets, such as for
+    /// indexing into a `Vec<char>` or reporting columns. Chars are counted incrementally from the
+    /// previous range, so the conversion costs O(n) in the content length overall.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let ranges = QueryRangeItr::new("needle", "café☕needle").collect_char_ranges();
+    /// assert_eq!(ranges, vec![5..11]);
+    /// ```
+    pub fn collect_char_ranges(&mut self) -> Vec<Range<usize>> {
+        let content = self.full_content;
+        let mut byte_offset: usize = 0;
+        let mut char_offset: usize = 0;
+        self.map(|range| {
+            char_offset += content[byte_offset..range.start].chars().count();
+            byte_offset = range.start;
+            let len = content[range].chars().count();
+            char_offset..(char_offset + len)
+        }).collect()
+    }
+
+    /// Checks if the query is found in the content at all, stopping at the first match. An empty
+    /// query is never found.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// assert!(QueryRangeItr::contains("needle", "haystackneedle"));
+    /// assert!(!QueryRangeItr::contains("Needle", "haystackneedle"));
+    /// ```
+    pub fn contains(query: &'a str, content: &'a str) -> bool {
+        Self::new(query, content).next().is_some()
+    }
+
+    /// Checks if the query is found in the content at all ignoring ASCII case, as with
+    /// `new_case_insensitive`, stopping at the first match.
+    pub fn contains_case_insensitive(query: &'a str, content: &'a str) -> bool {
+        Self::new_case_insensitive(query, content).next().is_some()
+    }
+
+    /// Counts the non-overlapping matches of the query in the content, the same way the iterator
+    /// finds them.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// assert_eq!(QueryRangeItr::count_matches("needle", "needleXneedle"), 2);
+    /// ```
+    pub fn count_matches(query: &'a str, content: &'a str) -> usize {
+        Self::new(query, content).count()
+    }
+
+    /// Gets the range of the `n`-th (zero-based) match of the query, or `None` if there are fewer
+    /// matches. The scan stops as soon as the match is found.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// assert_eq!(QueryRangeItr::nth_match("ab", "ab ab ab", 2), Some(6..8));
+    /// ```
+    pub fn nth_match(query: &'a str, content: &'a str, n: usize) -> Option<Range<usize>> {
+        Self::new(query, content).nth(n)
+    }
+
+    /// Gets the matched strings from the content, sorted lexicographically, as a shorthand for the
+    /// common `collect_strings` and sort follow-up. Use `collect_sorted_strings` for the matches of
+    /// other modes, such as case-insensitive matching, where the matched strings differ.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let sorted = QueryRangeItr::extract_sorted("b", "b B b");
+    /// assert_eq!(sorted, vec!["b", "b"]);
+    /// ```
+    pub fn extract_sorted(query: &'a str, content: &'a str) -> Vec<String> {
+        Self::new(query, content).collect_sorted_strings()
+    }
+
+    /// Private, walks all of the content in order, calling the closure with each matched or
+    /// non-matched segment and `true` if it's a match. Empty segments are skipped.
+    fn for_each_segment<F>(query: &'a str, content: &'a str, mut each: F) where F: FnMut(&'a str, bool) {
+        let mut last_end: usize = 0;
+        for range in Self::new(query, content) {
+            if range.start > last_end {
+                each(&content[last_end..range.start], false);
+            }
+            each(&content[range.clone()], true);
+            last_end = range.end;
+        }
+        if last_end < content.len() {
+            each(&content[last_end..], false);
+        }
+    }
+
+    /// Private, advances the iterator so that the search resumes at the given byte offset into the
+    /// full content.
+    fn skip_to(&mut self, offset: usize) {
+        self.current_content = &self.full_content[offset..];
+        self.removed_count = offset;
+    }
+
+    /// Private, finds the first accepted match of the query in the remaining content, returning its
+    /// range relative to the remaining content. A rejected candidate is skipped by a single char so
+    /// that an accepted match overlapping it is still found.
+    fn find_next(&self) -> Option<Range<usize>> {
+        let current_content = self.current_content;
+        let mut offset: usize = 0;
+        while let Some(range) = self.find_candidate(&current_content[offset..]) {
+            let range = (range.start + offset)..(range.end + offset);
+            if self.accepts(&range) {
+                return Some(range);
+            }
+            let skipped = current_content[range.start..].chars().next().map_or(1, char::len_utf8);
+            offset = range.start + skipped;
+        }
+        None
+    }
+
+    /// Private, finds the last match that forward iteration would yield within the first `end`
+    /// bytes of the remaining content, returning its range relative to the remaining content. A
+    /// plain query is found with `rfind` when that gives the same match, such as when the query
+    /// can't overlap itself. Otherwise (as with other matching options or a stride), this falls back
+    /// to a forward scan.
+    fn find_last(&self, end: usize) -> Option<Range<usize>> {
+        let content = &self.current_content[..end];
+        if self.is_literal() && (self.overlapping || !overlaps_itself(self.query)) {
+            return get_last_range(self.query, content);
+        }
+        let mut last: Option<Range<usize>> = None;
+        let mut offset: usize = 0;
+        while let Some(range) = self.find_candidate(&content[offset..]) {
+            let range = (range.start + offset)..(range.end + offset);
+            if self.accepts(&range) {
+                offset = self.resume_offset(content, &range);
+                last = Some(range);
+            } else {
+                offset = range.start + content[range.start..].chars().next().map_or(1, char::len_utf8);
+            }
+        }
+        last
+    }
+
+    /// Private, checks if the iterator finds the query as is, without any matching options or
+    /// stride.
+    fn is_literal(&self) -> bool {
+        self.stride == 0
+            && !self.word_suffix
+            && !self.whole_word
+            && !self.flexible_ws
+            && !self.case_insensitive
+            && self.escape.is_none()
+            && self.tolerate.is_none()
+    }
+
+    /// Private, gets the fewest bytes a match of the query can span, or zero if the query can never
+    /// match.
+    fn min_match_len(&self) -> usize {
+        if self.flexible_ws {
+            let words: Vec<&str> = self.query.split_whitespace().collect();
+            words.iter().map(|word| word.len()).sum::<usize>() + words.len().saturating_sub(1)
+        } else {
+            self.query.len()
+        }
+    }
+
+    /// Private, gets the most ranges the iterator could still yield, from the remaining content
+    /// length and the shortest possible match.
+    fn max_remaining(&self) -> usize {
+        let peeked = if self.peeked.is_some() { 1 } else { 0 };
+        if let Some(window) = self.window.as_ref() {
+            return window.len() + peeked;
+        }
+        let len = self.current_content.len();
+        let min_len = self.min_match_len();
+        let matches = match min_len {
+            0 => 0,
+            _ if self.overlapping => (len + 1).saturating_sub(min_len),
+            _ => len / min_len,
+        };
+        let ranges = match (self.inverted, len) {
+            (true, 0) => 0,
+            (true, _) => matches + 1,
+            (false, _) => matches,
+        };
+        let ranges = self.limit.map_or(ranges, |limit| ranges.min(limit));
+        ranges + peeked
+    }
+
+    /// Private, finds the first candidate match of the query in the given content, before any of
+    /// the iterator's matching options are checked.
+    fn find_candidate(&self, content: &str) -> Option<Range<usize>> {
+        if self.flexible_ws {
+            get_range_flexible_ws(self.query, content)
+        } else if let Some(optional) = self.tolerate {
+            get_range_tolerating(self.query, content, optional)
+        } else if self.case_insensitive {
+            get_range_ignore_ascii_case(self.query, content)
+        } else {
+            get_range(self.query, content)
+        }
+    }
+
+    /// Private, checks if a candidate match (relative to the remaining content) satisfies the
+    /// iterator's matching options. Checks are made against the full content, so the content
+    /// before the remaining content is taken into account.
+    fn accepts(&self, range: &Range<usize>) -> bool {
+        let start = range.start + self.removed_count;
+        let end = range.end + self.removed_count;
+        if self.word_suffix && !is_word_boundary_after(self.full_content, end) {
+            return false;
+        }
+        if self.whole_word && !is_whole_word(self.full_content, &(start..end)) {
+            return false;
+        }
+        if let Some(escape) = self.escape {
+            let escapes = self.full_content[..start].chars().rev().take_while(|c| *c == escape).count();
+            if escapes % 2 == 1 {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Private, gets where the search resumes after a match (relative to the remaining content),
+    /// which is past the match end or at least the stride from the match start. When overlapping,
+    /// it's just past the first char of the match instead.
+    fn resume_offset(&self, content: &str, range: &Range<usize>) -> usize {
+        if self.overlapping {
+            content[range.start..].chars().next().map_or(range.end, |c| range.start + c.len_utf8())
+        } else {
+            snap_boundary_up(content, range.start.saturating_add(self.stride)).max(range.end)
+        }
+    }
+
     /// Gets the next range that matches the given query.
     fn next_standard(&mut self) -> Option<Range<usize>> {
         let current_content = self.current_content;
-        let possible_range = get_range(self.query, current_content);
+        let possible_range = self.find_next();
         if let Some(range) = possible_range {
             if is_within(current_content, &range) {
-                let next_start = range.end;
+                let next_start = self.resume_offset(current_content, &range);
                 let possible_range = shift_range_in_content(range, Shift::Up(self.removed_count), self.full_content);
                 if let Some(range) = possible_range {
                     let start_len = current_content.len();
@@ -131,218 +661,2481 @@ impl<'a> QueryRangeItr<'a> {
         let current_content = self.current_content;
         let start_index: usize = 0;
         let len = current_content.len();
-        let range = get_range(self.query, current_content).unwrap_or(len..len);
+        let range = self.find_next().unwrap_or(len..len);
         let end_index = range.start;
         let next_start: usize = min(range.end, len);
         let possible_range = shift_range(start_index..end_index, Shift::Up(self.removed_count));
         self.current_content = &current_content[next_start..];
         let start_length = len;
         self.removed_count += start_length - self.current_content.len();
-        if current_content.len() > 0 {
+        if !current_content.is_empty() {
             possible_range
         } else {
             None
         }
     }
+
+    /// Gets the last range that matches the given query, shrinking the remaining content to end
+    /// before it. When overlapping, the content is only shrunk to just before the match end, so
+    /// that earlier matches overlapping it can still be found.
+    fn next_back_standard(&mut self) -> Option<Range<usize>> {
+        let range = self.find_last(self.current_content.len())?;
+        let keep = if self.overlapping {
+            snap_boundary_down(self.current_content, range.end - 1)
+        } else {
+            range.start
+        };
+        self.current_content = &self.current_content[..keep];
+        shift_range(range, Shift::Up(self.removed_count))
+    }
+
+    /// Gets the last range that doesn't match the given query, shrinking the remaining content so
+    /// that forward iteration yields the same ranges before it. As when iterating forward, an
+    /// empty range after a match at the very end is not yielded.
+    fn next_back_inverted(&mut self) -> Option<Range<usize>> {
+        let len = self.current_content.len();
+        if len == 0 {
+            return None;
+        }
+        let (range, keep) = match self.find_last(len) {
+            None => (0..len, 0),
+            Some(found) if found.end < len => (found.end..len, found.end),
+            Some(found) => {
+                let previous_end = self.find_last(found.start).map_or(0, |previous| previous.end);
+                (previous_end..found.start, previous_end)
+            }
+        };
+        self.current_content = &self.current_content[..keep];
+        shift_range(range, Shift::Up(self.removed_count))
+    }
 }
 
 // ----------------------------------------------------------------------------------------------- /
 
-/// Transform methods
+/// Adaptor methods
 impl<'a> QueryRangeItr<'a> {
 
-    /// Reassembles content, but transforms the query content or the non-query content
+    /// Wraps this iterator so that match count and total matched length are tracked while
+    /// iterating. The statistics are available from `StatsIter::stats()` at any point.
+    pub fn with_stats(self) -> StatsIter<'a> {
+        StatsIter::new(self)
+    }
+
+    /// Tags each range with its zero-based occurrence index, such as for acting on only some
+    /// occurrences (see `replace_indices`).
     ///
     /// **Example:**
     /// ```
     /// use query_range::QueryRangeItr;
     ///
-    /// let query = "needle";
-    /// let content = "haystackneedlehaystackneedlehaystack";
-    /// let result = QueryRangeItr::transform(query, content, |it| it.to_uppercase(), false);
-    /// assert_eq!(result, "haystackNEEDLEhaystackNEEDLEhaystack");
+    /// let indexed: Vec<_> = QueryRangeItr::new("ab", "ab-ab").enumerate_matches().collect();
+    /// assert_eq!(indexed, vec![(0, 0..2), (1, 3..5)]);
     /// ```
-    ///
-    /// **Parameters:**
-    /// - `query` - The search query
-    /// - `content` - The content to look for the query in
-    /// - `transform` -  A transform closure to run on all query content
-    /// - `invert` - If `true`, applies the transform to the non-query content
-    pub fn transform<T>(
-        query: &'a str,
-        content: &'a str,
-        transform: T,
-        invert: bool,
-    ) -> String where T: Fn(&str) -> String {
-        let selects = Self::new_base(query, content, invert);
-        let non_selects = Self::new_base(query, content, !invert);
-        let transform_each = |range: Range<usize>| {
-            let start = range.clone().start;
-            let original = &content[range];
-            let value = transform(original);
-            (value, start)
-        };
-        let transform_rest = |range: Range<usize>| {
-            let start = range.clone().start;
-            let original = &content[range];
-            let value = String::from(original);
-            (value, start)
-        };
-        let mut selected_subs: Vec<(String, usize)>  = selects.map(|range| transform_each(range)).collect();
-        let mut non_selected_subs: Vec<(String, usize)> = non_selects.map(|range| transform_rest(range)).collect();
-        selected_subs.append(&mut non_selected_subs);
-        let mut merged = selected_subs;
-        merged.sort_unstable_by(|s1,s2|  s1.1.cmp(&s2.1));
-        let strings: Vec<String> = merged.iter().map(|s| s.0.clone()).collect();
-        strings.join("")
+    pub fn enumerate_matches(self) -> impl Iterator<Item = (usize, Range<usize>)> + 'a {
+        self.enumerate()
     }
 
-    /// Reassembles content, but transforms the query content
+    /// Lazily turns each range into an owned copy of the matched content. Unlike
+    /// `collect_strings`, each `String` is only allocated when it's pulled from the iterator.
     ///
     /// **Example:**
     /// ```
     /// use query_range::QueryRangeItr;
     ///
-    /// let query = "needle";
     /// let content = "haystackneedlehaystackneedlehaystack";
-    /// let result = QueryRangeItr::transform_query(query, content, |it| it.to_uppercase());
-    /// assert_eq!(result, "haystackNEEDLEhaystackNEEDLEhaystack");
+    /// let first = QueryRangeItr::new("needle", content).into_strings().next();
+    /// assert_eq!(first, Some(String::from("needle")));
     /// ```
-    ///
-    /// **Parameters:**
-    /// - `query` - The search query
-    /// - `content` - The content to look for the query in
-    /// - `transform` -  A transform closure to run on all query content
-    pub fn transform_query<T>(
-        query: &'a str,
-        content: &'a str,
-        transform: T,
-    ) -> String where T: Fn(&str) -> String {
-        Self::transform(query, content, transform, false)
+    pub fn into_strings(self) -> impl Iterator<Item = String> + 'a {
+        let content = self.full_content;
+        self.map(move |range| String::from(&content[range]))
     }
 
-    /// Reassembles content, but transforms the non-query content
+    /// Collects the distinct matched strings, deduplicated while keeping the order in which each was
+    /// first seen. This is mostly useful with modes where the matched text can differ from match to
+    /// match.
     ///
     /// **Example:**
     /// ```
     /// use query_range::QueryRangeItr;
     ///
-    /// let query = "needle";
-    /// let content = "haystackneedlehaystackneedlehaystack";
-    /// let result = QueryRangeItr::transform_other(query, content, |it| it.to_uppercase());
-    /// assert_eq!(result, "HAYSTACKneedleHAYSTACKneedleHAYSTACK");
+    /// let unique = QueryRangeItr::new_inverted(",", "b,a,b,c,a").unique_strings();
+    /// assert_eq!(unique, vec!["b", "a", "c"]);
     /// ```
-    ///
-    /// **Parameters:**
+    pub fn unique_strings(self) -> Vec<String> {
+        let content = self.full_content;
+        let mut seen: HashSet<&str> = HashSet::new();
+        self.map(|range| &content[range])
+            .filter(|found| seen.insert(found))
+            .map(String::from)
+            .collect()
+    }
+
+    /// Pairs each range with both its byte length and the number of characters in the matched
+    /// content, which differ for multibyte content.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let lengths: Vec<_> = QueryRangeItr::new("né", "ané").with_lengths().collect();
+    /// assert_eq!(lengths, vec![(1..4, 3, 2)]);
+    /// ```
+    pub fn with_lengths(self) -> impl Iterator<Item = (Range<usize>, usize, usize)> + 'a {
+        let content = self.full_content;
+        self.map(move |range| {
+            let char_len = content[range.clone()].chars().count();
+            (range.clone(), range.len(), char_len)
+        })
+    }
+
+    /// Pairs each range with the matched slice of the content, like `str::match_indices`, so the
+    /// content doesn't need to be sliced again.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let matches: Vec<_> = QueryRangeItr::new("b", "abcb").with_str().collect();
+    /// assert_eq!(matches, vec![(1..2, "b"), (3..4, "b")]);
+    /// ```
+    pub fn with_str(self) -> impl Iterator<Item = (Range<usize>, &'a str)> + 'a {
+        let content = self.full_content;
+        self.map(move |range| (range.clone(), &content[range]))
+    }
+
+    /// Pairs each range with the byte length of the gap before it, measured from the end of the
+    /// previous range (or the start of the content for the first range).
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let content = "haystackneedlehaystackneedlehaystack";
+    /// let gaps: Vec<_> = QueryRangeItr::new("needle", content).with_gap_before().collect();
+    /// assert_eq!(gaps, vec![(8, 8..14), (8, 22..28)]);
+    /// ```
+    pub fn with_gap_before(self) -> impl Iterator<Item = (usize, Range<usize>)> + 'a {
+        let mut previous_end: usize = 0;
+        self.map(move |range| {
+            let gap = range.start.saturating_sub(previous_end);
+            previous_end = range.end;
+            (gap, range)
+        })
+    }
+
+    /// Pairs each range with the byte length of the content left to search after it, such as for
+    /// progress reporting during long scans.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let progress: Vec<_> = QueryRangeItr::new("ab", "abxxabx").with_progress().collect();
+    /// assert_eq!(progress, vec![(0..2, 5), (4..6, 1)]);
+    /// ```
+    pub fn with_progress(self) -> impl Iterator<Item = (Range<usize>, usize)> + 'a {
+        let mut itr = self;
+        std::iter::from_fn(move || {
+            let range = itr.next()?;
+            Some((range, itr.current_content.len()))
+        })
+    }
+
+    /// Converts each range to UTF-16 code unit offsets, such as for editors and LSP tooling. Offsets
+    /// are counted incrementally from the previous match, so the whole content is only walked once.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let ranges: Vec<_> = QueryRangeItr::new("b", "é🦀b").utf16_ranges().collect();
+    /// assert_eq!(ranges, vec![3..4]);
+    /// ```
+    pub fn utf16_ranges(self) -> impl Iterator<Item = Range<usize>> + 'a {
+        let content = self.full_content;
+        let mut byte_offset: usize = 0;
+        let mut utf16_offset: usize = 0;
+        self.map(move |range| {
+            utf16_offset += content[byte_offset..range.start].encode_utf16().count();
+            byte_offset = range.start;
+            let len = content[range].encode_utf16().count();
+            utf16_offset..(utf16_offset + len)
+        })
+    }
+
+    /// Converts each range to another integer type, such as `u32` offsets for binary formats. Any
+    /// range with an offset that doesn't fit in the target type is yielded as an error.
+    ///
+    /// **Example:**
+    /// ```
+    /// use std::ops::Range;
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let content = "haystackneedlehaystack";
+    /// let ranges: Vec<Range<u32>> = QueryRangeItr::new("needle", content)
+    ///     .ranges_as::<u32>()
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    /// assert_eq!(ranges, vec![8..14]);
+    /// ```
+    pub fn ranges_as<T>(self) -> impl Iterator<Item = Result<Range<T>, TryFromIntError>> + 'a
+        where T: PrimInt + TryFrom<usize, Error = TryFromIntError> + 'a
+    {
+        self.map(|range| Ok(T::try_from(range.start)?..T::try_from(range.end)?))
+    }
+}
+
+// ----------------------------------------------------------------------------------------------- /
+
+/// Search methods
+impl<'a> QueryRangeItr<'a> {
+
+    /// Iterates matches of the query only while they stay dense: iteration stops at the first
+    /// match separated from the previous one by a gap of more than `max_gap` bytes. The gap before
+    /// the first match is not considered.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let content = "ab-ab--ab-------ab";
+    /// let ranges: Vec<_> = QueryRangeItr::while_dense("ab", content, 2).collect();
+    /// assert_eq!(ranges, vec![0..2, 3..5, 7..9]);
+    /// ```
+    ///
+    /// **Parameters:**
+    /// - `query` - The search query
+    /// - `content` - The content to look for the query in
+    /// - `max_gap` - The largest gap (in bytes) allowed between consecutive matches
+    pub fn while_dense(query: &'a str, content: &'a str, max_gap: usize) -> impl Iterator<Item = Range<usize>> + 'a {
+        Self::new(query, content)
+            .with_gap_before()
+            .enumerate()
+            .take_while(move |(index, (gap, _))| *index == 0 || *gap <= max_gap)
+            .map(|(_, (_, range))| range)
+    }
+
+    /// Iterates matches of the query, flagging each with whether it stands alone as a whole word
+    /// (both sides are word boundaries). Unlike a whole-word search, no match is filtered out.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let flagged: Vec<_> = QueryRangeItr::with_word_boundary_flag("cat", "cat in concatenate").collect();
+    /// assert_eq!(flagged, vec![(0..3, true), (10..13, false)]);
+    /// ```
+    pub fn with_word_boundary_flag(query: &'a str, content: &'a str) -> impl Iterator<Item = (Range<usize>, bool)> + 'a {
+        Self::new(query, content).map(move |range| {
+            let is_word = is_whole_word(content, &range);
+            (range, is_word)
+        })
+    }
+
+    /// Finds all matches of all the given queries and merges any that overlap or touch, yielding
+    /// the resulting non-overlapping spans in order. This is useful for highlighting several
+    /// keywords without double-wrapping overlapping matches.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let spans: Vec<_> = QueryRangeItr::new_multi_merged(&["foobar", "bar"], "a foobar b").collect();
+    /// assert_eq!(spans, vec![2..8]);
+    /// ```
+    pub fn new_multi_merged(queries: &[&str], content: &str) -> impl Iterator<Item = Range<usize>> {
+        let ranges: Vec<Range<usize>> = queries
+            .iter()
+            .flat_map(|query| QueryRangeItr::new(query, content))
+            .collect();
+        merge_ranges(ranges).into_iter()
+    }
+
+    /// Finds all non-overlapping matches of any of the given queries, in ascending start order,
+    /// yielding each query's index with its range, such as for highlighting several keywords at
+    /// once. Overlapping matches are resolved by the earliest start, then the longest match (see
+    /// `new_multi_with` to choose another tie-break policy).
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let content = "warn: fatal error";
+    /// let matches: Vec<_> = QueryRangeItr::new_multi(&["error", "warn", "fatal"], content).collect();
+    /// assert_eq!(matches, vec![(1, 0..4), (2, 6..11), (0, 12..17)]);
+    /// ```
+    pub fn new_multi(queries: &[&str], content: &str) -> impl Iterator<Item = (usize, Range<usize>)> {
+        Self::new_multi_with(queries, content, TieBreak::LongestMatch)
+    }
+
+    /// Finds all non-overlapping matches of any of the given queries in a single left-to-right pass,
+    /// yielding each query's index with its range. Where several queries match at the same
+    /// position, the tie-break policy chooses which one is yielded. The search then resumes after
+    /// the chosen match.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::{QueryRangeItr, TieBreak};
+    ///
+    /// let queries = ["foo", "foobar"];
+    /// let matches: Vec<_> = QueryRangeItr::new_multi_with(&queries, "foobar", TieBreak::LongestMatch).collect();
+    /// assert_eq!(matches, vec![(1, 0..6)]);
+    /// ```
+    ///
+    /// **Parameters:**
+    /// - `queries` - The search queries
+    /// - `content` - The content to look for the queries in
+    /// - `tie_break` - The policy for choosing between queries matching at the same position
+    pub fn new_multi_with(
+        queries: &[&str],
+        content: &str,
+        tie_break: TieBreak,
+    ) -> impl Iterator<Item = (usize, Range<usize>)> {
+        let mut upcoming: Vec<Option<Range<usize>>> =
+            queries.iter().map(|query| get_range(query, content)).collect();
+        let mut matches: Vec<(usize, Range<usize>)> = Vec::new();
+        let mut offset: usize = 0;
+        loop {
+            for (query, found) in queries.iter().zip(upcoming.iter_mut()) {
+                if found.as_ref().is_some_and(|range| range.start < offset) {
+                    *found = get_range(query, &content[offset..])
+                        .map(|range| (range.start + offset)..(range.end + offset));
+                }
+            }
+            let start = match upcoming.iter().flatten().map(|range| range.start).min() {
+                Some(start) => start,
+                None => break,
+            };
+            let mut candidates = upcoming
+                .iter()
+                .enumerate()
+                .filter_map(|(index, found)| found.clone().map(|range| (index, range)))
+                .filter(|(_, range)| range.start == start);
+            let chosen = match tie_break {
+                TieBreak::FirstQuery => candidates.next(),
+                TieBreak::LongestMatch => candidates.min_by_key(|(_, range)| Reverse(range.len())),
+                TieBreak::ShortestMatch => candidates.min_by_key(|(_, range)| range.len()),
+            };
+            if let Some((index, range)) = chosen {
+                offset = range.end;
+                matches.push((index, range));
+            }
+        }
+        matches.into_iter()
+    }
+
+    /// Iterates each maximal run of `unit` repeated with `sep` between copies. For example, with
+    /// unit `"ab"` and separator `"-"`, the content `"ab-ab-abXab"` yields the span `"ab-ab-ab"`
+    /// and then `"ab"`. A trailing separator is not included in a run.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let content = "ab-ab-abXab";
+    /// let runs: Vec<_> = QueryRangeItr::new_repeated("ab", "-", content).collect();
+    /// assert_eq!(runs, vec![0..8, 9..11]);
+    /// ```
+    pub fn new_repeated(unit: &'a str, sep: &'a str, content: &'a str) -> impl Iterator<Item = Range<usize>> + 'a {
+        let mut units = Self::new(unit, content);
+        std::iter::from_fn(move || {
+            let first = units.next()?;
+            let mut end = first.end;
+            loop {
+                let rest = &content[end..];
+                if !rest.starts_with(sep) || !rest[sep.len()..].starts_with(unit) {
+                    break;
+                }
+                end += sep.len() + unit.len();
+            }
+            units.skip_to(end);
+            Some(first.start..end)
+        })
+    }
+
+    /// Iterates the byte range of each match of a custom matcher, for matching rules that a plain
+    /// query can't express (see `repeat_matcher`). The matcher is called with the rest of the
+    /// content from each char boundary in turn, and returns the byte length of the match starting
+    /// there, or `None` if there isn't one. After a match the search resumes at its end (snapped up
+    /// to a char boundary); empty matches are ignored.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::{QueryRangeItr, repeat_matcher};
+    ///
+    /// let ranges: Vec<_> = QueryRangeItr::new_with_matcher("ab abab", repeat_matcher("ab", 2, 3)).collect();
+    /// assert_eq!(ranges, vec![3..7]);
+    /// ```
+    ///
+    /// **Parameters:**
+    /// - `content` - The content to look for matches in
+    /// - `matcher` - Gets the byte length of the match at the start of the content it's given
+    pub fn new_with_matcher<F>(content: &'a str, matcher: F) -> impl Iterator<Item = Range<usize>> + 'a
+        where F: Fn(&str) -> Option<usize> + 'a
+    {
+        let mut offset: usize = 0;
+        std::iter::from_fn(move || {
+            while let Some(character) = content[offset..].chars().next() {
+                let start = offset;
+                match matcher(&content[start..]) {
+                    Some(len) if len > 0 => {
+                        offset = snap_boundary_up(content, start.saturating_add(len));
+                        return Some(start..offset);
+                    }
+                    _ => offset += character.len_utf8(),
+                }
+            }
+            None
+        })
+    }
+
+    /// Iterates the byte range of each match of the glob pattern, where `*` matches any run of
+    /// characters (including none) and `?` matches exactly one character. Where several matches
+    /// start at the same position, the *shortest* is taken, so `"a*b"` stops at the first `b`
+    /// (use `new_glob_greedy` to take the longest instead). An empty pattern never matches.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let ranges: Vec<_> = QueryRangeItr::new_glob("a*b", "aXbYb").collect();
+    /// assert_eq!(ranges, vec![0..3]);
+    /// ```
+    pub fn new_glob(pattern: &'a str, content: &'a str) -> impl Iterator<Item = Range<usize>> + 'a {
+        Self::new_with_matcher(content, move |rest: &str| glob_match_len(pattern, rest, false))
+    }
+
+    /// Iterates the byte range of each match of the glob pattern, like `new_glob`, except that at
+    /// each match start the match extends to the *longest* possible match before moving on, so
+    /// `"a*b"` runs to the last `b` in the content.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let ranges: Vec<_> = QueryRangeItr::new_glob_greedy("a*b", "aXbYb").collect();
+    /// assert_eq!(ranges, vec![0..5]);
+    /// ```
+    pub fn new_glob_greedy(pattern: &'a str, content: &'a str) -> impl Iterator<Item = Range<usize>> + 'a {
+        Self::new_with_matcher(content, move |rest: &str| glob_match_len(pattern, rest, true))
+    }
+
+    /// Iterates the byte range of each outermost balanced group opened by `open` and closed by
+    /// `close`, including the delimiters. Nested groups are part of their outermost group, so
+    /// `"(a(b)c)"` yields a single range covering all of it.
+    ///
+    /// When `open` and `close` are the same character, such as for quotes, each one closes the
+    /// group opened by the one before it, so these groups can't nest.
+    ///
+    /// Unbalanced input is handled gracefully: a `close` without a matching `open` is skipped, and
+    /// a group that is still open at the end of the content is not yielded.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let groups: Vec<_> = QueryRangeItr::new_balanced('(', ')', "f(a(b)c) + g(d)").collect();
+    /// assert_eq!(groups, vec![1..8, 12..15]);
+    /// ```
+    pub fn new_balanced(open: char, close: char, content: &'a str) -> impl Iterator<Item = Range<usize>> + 'a {
+        let mut chars = content.char_indices();
+        std::iter::from_fn(move || {
+            let mut depth: usize = 0;
+            let mut start: usize = 0;
+            for (index, character) in chars.by_ref() {
+                if character == close && depth > 0 {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(start..(index + close.len_utf8()));
+                    }
+                } else if character == open {
+                    if depth == 0 {
+                        start = index;
+                    }
+                    depth += 1;
+                }
+            }
+            None
+        })
+    }
+
+    /// Iterates the byte length of each gap between consecutive matches of the query, for running
+    /// custom analysis over match spacing. The leading gap before the first match and the trailing
+    /// gap after the last one are not included (`with_gap_before` also reports the leading gap).
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let gaps: Vec<usize> = QueryRangeItr::gaps("ab", "xxab-ab--ab").collect();
+    /// assert_eq!(gaps, vec![1, 2]);
+    /// ```
+    pub fn gaps(query: &'a str, content: &'a str) -> impl Iterator<Item = usize> + 'a {
+        Self::new(query, content)
+            .with_gap_before()
+            .skip(1)
+            .map(|(gap, _)| gap)
+    }
+
+    /// Counts matches of the query found while ignoring ASCII case, broken down by how each match
+    /// is actually cased in the content.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let counts = QueryRangeItr::count_by_case_insensitive("cat", "Cat cat CAT cat");
+    /// assert_eq!(counts["cat"], 2);
+    /// assert_eq!(counts["CAT"], 1);
+    /// ```
+    pub fn count_by_case_insensitive(query: &str, content: &str) -> HashMap<String, usize> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for range in QueryRangeItr::new_case_insensitive(query, content) {
+            *counts.entry(String::from(&content[range])).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Gets the range of each match of the query extended by up to `radius` chars of context on
+    /// each side, clamped at the edges of the content, such as for search previews. The radius is
+    /// counted in chars, so multibyte content is never split. Windows of nearby matches may
+    /// overlap; they are not merged, so there's always one window per match.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let content = "haystackneedlehaystack";
+    /// let windows = QueryRangeItr::contexts("needle", content, 3);
+    /// assert_eq!(windows, vec![5..17]);
+    /// assert_eq!(&content[5..17], "ackneedlehay");
+    /// ```
+    pub fn contexts(query: &'a str, content: &'a str, radius: usize) -> Vec<Range<usize>> {
+        Self::new(query, content)
+            .map(|range| expand_by_chars(content, &range, radius))
+            .collect()
+    }
+
+    /// Iterates matches of the query, each packaged as a snippet of surrounding context and the
+    /// range of the match within that snippet. The context extends up to `radius` characters on
+    /// each side of the match, clamped at the edges of the content.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let content = "haystackneedlehaystack";
+    /// let snippet = QueryRangeItr::snippets("needle", content, 3).next().unwrap();
+    /// assert_eq!(snippet.text, "ackneedlehay");
+    /// assert_eq!(snippet.match_in_snippet, 3..9);
+    /// ```
+    pub fn snippets(query: &'a str, content: &'a str, radius: usize) -> impl Iterator<Item = Snippet> + 'a {
+        Self::new(query, content).map(move |range| {
+            let window = expand_by_chars(content, &range, radius);
+            Snippet {
+                text: String::from(&content[window.clone()]),
+                match_in_snippet: (range.start - window.start)..(range.end - window.start),
+            }
+        })
+    }
+
+    /// Finds approximate matches of the query, ranked best-first. A fuzzy match is a slice of the
+    /// content within `max_distance` character edits (Levenshtein distance) of the query. Each
+    /// match is returned with its distance, sorted ascending by distance and then by position.
+    ///
+    /// At each position, only the closest slice is considered, and where candidates overlap the one
+    /// with the lower distance (then the earlier one) is kept, so the returned ranges never overlap.
+    /// This compares every window of the content, so it's intended for modest content sizes.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let ranked = QueryRangeItr::fuzzy_ranked("needle", "nedle ... needle", 1);
+    /// assert_eq!(ranked, vec![(10..16, 0), (0..5, 1)]);
+    /// ```
+    pub fn fuzzy_ranked(query: &str, content: &str, max_distance: usize) -> Vec<(Range<usize>, usize)> {
+        let query_len = query.chars().count();
+        if query_len == 0 {
+            return Vec::new();
+        }
+        let boundaries: Vec<usize> = content
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain(std::iter::once(content.len()))
+            .collect();
+        let char_count = boundaries.len() - 1;
+        let min_len = query_len.saturating_sub(max_distance).max(1);
+        let mut candidates: Vec<(Range<usize>, usize)> = Vec::new();
+        for start in 0..char_count {
+            let max_len = (query_len + max_distance).min(char_count - start);
+            let best = (min_len..=max_len)
+                .map(|len| {
+                    let range = boundaries[start]..boundaries[start + len];
+                    let distance = edit_distance(query, &content[range.clone()]);
+                    (distance, len.abs_diff(query_len), range)
+                })
+                .min_by_key(|(distance, len_diff, range)| (*distance, *len_diff, range.end));
+            if let Some((distance, _, range)) = best {
+                if distance <= max_distance {
+                    candidates.push((range, distance));
+                }
+            }
+        }
+        candidates.sort_by_key(|(range, distance)| (*distance, range.start));
+        let mut ranked: Vec<(Range<usize>, usize)> = Vec::new();
+        for (range, distance) in candidates {
+            let overlaps = ranked.iter().any(|(kept, _)| range.start < kept.end && kept.start < range.end);
+            if !overlaps {
+                ranked.push((range, distance));
+            }
+        }
+        ranked
+    }
+
+    /// Splits the content on the first match of the query, returning the content before and after
+    /// the match (excluding the match itself), like `str::split_once`. Returns `None` if the query
+    /// isn't found.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// assert_eq!(QueryRangeItr::split_once("=", "a=b=c"), Some(("a", "b=c")));
+    /// assert_eq!(QueryRangeItr::split_once("=", "abc"), None);
+    /// ```
+    pub fn split_once(query: &'a str, content: &'a str) -> Option<(&'a str, &'a str)> {
+        let range = Self::new(query, content).next()?;
+        Some((&content[..range.start], &content[range.end..]))
+    }
+
+    /// Splits all of the content, in order, into segments that either match the query or lie in
+    /// between matches. Empty segments are skipped, so the segments' ranges tile the content.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::{QueryRangeItr, Segment};
+    ///
+    /// let segments = QueryRangeItr::segments("b", "abc");
+    /// assert_eq!(segments, vec![Segment::Other(0..1), Segment::Match(1..2), Segment::Other(2..3)]);
+    /// ```
+    pub fn segments(query: &'a str, content: &'a str) -> Vec<Segment> {
+        let mut segments: Vec<Segment> = Vec::new();
+        let mut last_end: usize = 0;
+        for range in Self::new(query, content) {
+            if range.start > last_end {
+                segments.push(Segment::Other(last_end..range.start));
+            }
+            last_end = range.end;
+            segments.push(Segment::Match(range));
+        }
+        if last_end < content.len() {
+            segments.push(Segment::Other(last_end..content.len()));
+        }
+        segments
+    }
+
+    /// Splits the content on each match of the query, like `str::split`, yielding the segments in
+    /// between. Unlike the inverted iterator, every segment is yielded, so there are empty segments
+    /// where matches are adjacent or the content starts or ends with the query. Content without a
+    /// match (including with an empty query) is yielded whole, as a single segment.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let segments: Vec<&str> = QueryRangeItr::split("needle", "needleAneedleBneedle").collect();
+    /// assert_eq!(segments, vec!["", "A", "B", ""]);
+    /// ```
+    pub fn split(query: &'a str, content: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        let mut matches = Self::new(query, content);
+        let mut last_end: Option<usize> = Some(0);
+        std::iter::from_fn(move || {
+            let start = last_end?;
+            match matches.next() {
+                Some(range) => {
+                    last_end = Some(range.end);
+                    Some(&content[start..range.start])
+                }
+                None => {
+                    last_end = None;
+                    Some(&content[start..])
+                }
+            }
+        })
+    }
+
+    /// Splits the content into segments that each end with a match of the query (included in the
+    /// segment), like `str::split_inclusive`. The last segment is whatever follows the final match,
+    /// if anything does.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let segments: Vec<&str> = QueryRangeItr::split_inclusive(",", "a,b,c").collect();
+    /// assert_eq!(segments, vec!["a,", "b,", "c"]);
+    /// ```
+    pub fn split_inclusive(query: &'a str, content: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        let mut matches = Self::new(query, content);
+        let mut last_end: usize = 0;
+        std::iter::from_fn(move || {
+            let start = last_end;
+            match matches.next() {
+                Some(range) => {
+                    last_end = range.end;
+                    Some(&content[start..range.end])
+                }
+                None if start < content.len() => {
+                    last_end = content.len();
+                    Some(&content[start..])
+                }
+                None => None,
+            }
+        })
+    }
+
+    /// Sends each match range of the query across a channel as soon as it is found, so that a
+    /// consumer (for example, on another thread) can process matches while the search runs. Each
+    /// send blocks as `Sender::send` does. Stops and returns the error if the receiver hangs up.
+    ///
+    /// **Example:**
+    /// ```
+    /// use std::sync::mpsc::channel;
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let (tx, rx) = channel();
+    /// QueryRangeItr::stream_to("needle", "haystackneedlehaystack", tx).unwrap();
+    /// assert_eq!(rx.recv(), Ok(8..14));
+    /// ```
+    pub fn stream_to(query: &'a str, content: &'a str, tx: Sender<Range<usize>>) -> Result<(), SendError<Range<usize>>> {
+        for range in Self::new(query, content) {
+            tx.send(range)?;
+        }
+        Ok(())
+    }
+}
+
+// ----------------------------------------------------------------------------------------------- /
+
+/// Transform methods
+impl<'a> QueryRangeItr<'a> {
+
+    /// Visits the whole content in order, calling `on_match` for each match of the query and
+    /// `on_other` for each segment of content in between. This is a streaming way to reassemble
+    /// content without buffering the segments. Empty segments are not visited.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let mut result = String::new();
+    /// let mut matches = 0;
+    /// QueryRangeItr::visit_segments(
+    ///     "needle",
+    ///     "haystackneedlehaystack",
+    ///     |_| matches += 1,
+    ///     |other| result.push_str(other),
+    /// );
+    /// assert_eq!(matches, 1);
+    /// assert_eq!(result, "haystackhaystack");
+    /// ```
+    ///
+    /// **Parameters:**
+    /// - `query` - The search query
+    /// - `content` - The content to look for the query in
+    /// - `on_match` - A closure called with each match of the query
+    /// - `on_other` - A closure called with each segment between matches
+    pub fn visit_segments<M, O>(query: &'a str, content: &'a str, mut on_match: M, mut on_other: O)
+        where
+            M: FnMut(&'a str),
+            O: FnMut(&'a str),
+    {
+        Self::for_each_segment(query, content, |segment, is_match| {
+            if is_match {
+                on_match(segment)
+            } else {
+                on_other(segment)
+            }
+        });
+    }
+
+    /// Reassembles content, but transforms the query content or the non-query content. The content
+    /// is walked once, in order, pushing each segment straight into the result.
+    ///
+    /// When transforming the non-query content, the (empty) segments before a match at the start
+    /// of the content and between adjacent matches are transformed too, while an empty segment
+    /// after a match at the very end isn't, just as the inverted iterator yields them.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let query = "needle";
+    /// let content = "haystackneedlehaystackneedlehaystack";
+    /// let result = QueryRangeItr::transform(query, content, |it| it.to_uppercase(), false);
+    /// assert_eq!(result, "haystackNEEDLEhaystackNEEDLEhaystack");
+    /// ```
+    ///
+    /// **Parameters:**
+    /// - `query` - The search query
+    /// - `content` - The content to look for the query in
+    /// - `transform` -  A transform closure to run on all query content
+    /// - `invert` - If `true`, applies the transform to the non-query content
+    pub fn transform<T>(
+        query: &'a str,
+        content: &'a str,
+        transform: T,
+        invert: bool,
+    ) -> String where T: Fn(&str) -> String {
+        Self::transform_cow(query, content, transform, invert).into_owned()
+    }
+
+    /// Reassembles content like `transform`, but borrows the content as is when there's nothing to
+    /// transform (no matches or, when inverted, empty content), so no allocation happens.
+    ///
+    /// **Example:**
+    /// ```
+    /// use std::borrow::Cow;
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let result = QueryRangeItr::transform_cow("needle", "haystack", |it| it.to_uppercase(), false);
+    /// assert!(matches!(result, Cow::Borrowed("haystack")));
+    /// ```
+    ///
+    /// **Parameters:**
+    /// - `query` - The search query
+    /// - `content` - The content to look for the query in
+    /// - `transform` -  A transform closure to run on all query content
+    /// - `invert` - If `true`, applies the transform to the non-query content
+    pub fn transform_cow<T>(
+        query: &'a str,
+        content: &'a str,
+        transform: T,
+        invert: bool,
+    ) -> Cow<'a, str> where T: Fn(&str) -> String {
+        let mut matches = Self::new(query, content).peekable();
+        if matches.peek().is_none() && (!invert || content.is_empty()) {
+            return Cow::Borrowed(content);
+        }
+        let mut result = String::with_capacity(content.len());
+        Self::push_segments(content, matches, |segment, is_match| {
+            if is_match != invert {
+                result.push_str(&transform(segment));
+            } else {
+                result.push_str(segment);
+            }
+        });
+        Cow::Owned(result)
+    }
+
+    /// Reassembles content, but transforms the query content
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let query = "needle";
+    /// let content = "haystackneedlehaystackneedlehaystack";
+    /// let result = QueryRangeItr::transform_query(query, content, |it| it.to_uppercase());
+    /// assert_eq!(result, "haystackNEEDLEhaystackNEEDLEhaystack");
+    /// ```
+    ///
+    /// **Parameters:**
+    /// - `query` - The search query
+    /// - `content` - The content to look for the query in
+    /// - `transform` -  A transform closure to run on all query content
+    pub fn transform_query<T>(
+        query: &'a str,
+        content: &'a str,
+        transform: T,
+    ) -> String where T: Fn(&str) -> String {
+        Self::transform(query, content, transform, false)
+    }
+
+    /// Reassembles content, but transforms the non-query content
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let query = "needle";
+    /// let content = "haystackneedlehaystackneedlehaystack";
+    /// let result = QueryRangeItr::transform_other(query, content, |it| it.to_uppercase());
+    /// assert_eq!(result, "HAYSTACKneedleHAYSTACKneedleHAYSTACK");
+    /// ```
+    ///
+    /// **Parameters:**
+    /// - `query` - The search query
+    /// - `content` - The content to look for the query in
+    /// - `transform` -  A transform closure to run on all non-query content
+    pub fn transform_other<T>(
+        query: &'a str,
+        content: &'a str,
+        transform: T,
+    ) -> String where T: Fn(&str) -> String {
+        Self::transform(query, content, transform, true)
+    }
+
+    /// Reassembles content, but transforms both the query content and the non-query content. Both
+    /// transforms are applied in a single ordered pass over the original matches, so transformed
+    /// text is never searched again.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    /// use query_range::utility::to_title_case;
+    ///
+    /// let query = "needle";
+    /// let content = "haystackneedlehaystackneedlehaystack";
+    /// let result = QueryRangeItr::transform_all(
+    ///     query,
+    ///     content,
+    ///     |it| it.to_uppercase(),
+    ///     |it| to_title_case(it),
+    /// );
+    /// assert_eq!(result, "HaystackNEEDLEHaystackNEEDLEHaystack");
+    /// ```
+    ///
+    /// **Parameters:**
+    /// - `query` - The search query
+    /// - `content` - The content to look for the query in
+    /// - `transform_query` -  A transform closure to run on all query content
+    /// - `transform_non_query` - A transform closure to run on all non-query content
+    pub fn transform_all<TQ, TNQ>(
+        query: &'a str,
+        content: &'a str,
+        transform_query: TQ,
+        transform_non_query: TNQ,
+    ) -> String
+        where
+            TQ: Fn(&str) -> String,
+            TNQ: Fn(&str) -> String,
+    {
+        let mut result = String::with_capacity(content.len());
+        Self::push_segments(content, Self::new(query, content), |segment, is_match| {
+            if is_match {
+                result.push_str(&transform_query(segment));
+            } else {
+                result.push_str(&transform_non_query(segment));
+            }
+        });
+        result
+    }
+
+    /// Private, walks the content in order given its match ranges, calling the closure with each
+    /// match or segment in between and `true` if it's a match. As the inverted iterator yields
+    /// them, empty segments before a match are included, but not an empty segment at the very end.
+    fn push_segments<I, F>(content: &str, matches: I, mut push: F)
+        where
+            I: Iterator<Item = Range<usize>>,
+            F: FnMut(&str, bool),
+    {
+        let mut last_end: usize = 0;
+        for range in matches {
+            push(&content[last_end..range.start], false);
+            push(&content[range.clone()], true);
+            last_end = range.end;
+        }
+        if last_end < content.len() {
+            push(&content[last_end..], false);
+        }
+    }
+
+    /// Reassembles content, transforming each match with a closure that also receives the match's
+    /// zero-based occurrence index, such as for numbering matches. The non-query content is copied
+    /// as is.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let result = QueryRangeItr::transform_indexed("x", "x x", |index, it| format!("{}{}", it, index));
+    /// assert_eq!(result, "x0 x1");
+    /// ```
+    ///
+    /// **Parameters:**
+    /// - `query` - The search query
+    /// - `content` - The content to look for the query in
+    /// - `transform` - A transform closure to run on each match, given its occurrence index
+    pub fn transform_indexed<T>(query: &'a str, content: &'a str, transform: T) -> String
+        where T: Fn(usize, &str) -> String
+    {
+        let mut result = String::with_capacity(content.len());
+        let mut index: usize = 0;
+        Self::push_segments(content, Self::new(query, content), |segment, is_match| {
+            if is_match {
+                result.push_str(&transform(index, segment));
+                index += 1;
+            } else {
+                result.push_str(segment);
+            }
+        });
+        result
+    }
+
+    /// Reassembles content, transforming the query content, and caps the result at `max_chars`
+    /// characters for previews. If the result is longer, it's cut on a character boundary and an
+    /// ellipsis (`…`) is appended.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let query = "needle";
+    /// let content = "haystackneedlehaystackneedlehaystack";
+    /// let result = QueryRangeItr::transform_truncated(query, content, |it| it.to_uppercase(), 11);
+    /// assert_eq!(result, "haystackNEE…");
+    /// ```
+    ///
+    /// **Parameters:**
+    /// - `query` - The search query
+    /// - `content` - The content to look for the query in
+    /// - `transform` -  A transform closure to run on all query content
+    /// - `max_chars` - The maximum number of characters to keep (not counting the ellipsis)
+    pub fn transform_truncated<T>(
+        query: &'a str,
+        content: &'a str,
+        transform: T,
+        max_chars: usize,
+    ) -> String where T: Fn(&str) -> String {
+        let transformed = Self::transform_query(query, content, transform);
+        match transformed.char_indices().nth(max_chars) {
+            Some((cut, _)) => format!("{}…", &transformed[..cut]),
+            None => transformed,
+        }
+    }
+
+    /// Replaces every match of the query with the replacement. Matches are found the same way the
+    /// iterator finds them, and the replacement is never re-scanned, so a replacement containing
+    /// the query is inserted as is.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let result = QueryRangeItr::replace("needle", "haystackneedlehaystack", "pin");
+    /// assert_eq!(result, "haystackpinhaystack");
+    /// ```
+    ///
+    /// **Parameters:**
+    /// - `query` - The search query
+    /// - `content` - The content to look for the query in
+    /// - `replacement` - The text to replace each match with
+    pub fn replace(query: &'a str, content: &'a str, replacement: &str) -> String {
+        Self::replace_n(query, content, replacement, usize::MAX)
+    }
+
+    /// Replaces the first `n` matches of the query with the replacement, leaving the rest as they
+    /// are. With `n` of zero the content is unchanged, and with `n` larger than the match count
+    /// every match is replaced.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let result = QueryRangeItr::replace_n("x", "x x x", "y", 2);
+    /// assert_eq!(result, "y y x");
+    /// ```
+    ///
+    /// **Parameters:**
+    /// - `query` - The search query
+    /// - `content` - The content to look for the query in
+    /// - `replacement` - The text to replace each match with
+    /// - `n` - The maximum number of matches to replace
+    pub fn replace_n(query: &'a str, content: &'a str, replacement: &str, n: usize) -> String {
+        let mut result = String::with_capacity(content.len());
+        let mut last_end: usize = 0;
+        for range in Self::new(query, content).take(n) {
+            result.push_str(&content[last_end..range.start]);
+            result.push_str(replacement);
+            last_end = range.end;
+        }
+        result.push_str(&content[last_end..]);
+        result
+    }
+
+    /// Replaces only the matches whose zero-based occurrence index is in `indices`, leaving the
+    /// other matches as they are. Indices without a matching occurrence are ignored.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let result = QueryRangeItr::replace_indices("x", "x x x x", "y", &[0, 2]);
+    /// assert_eq!(result, "y x y x");
+    /// ```
+    ///
+    /// **Parameters:**
+    /// - `query` - The search query
+    /// - `content` - The content to look for the query in
+    /// - `replacement` - The text to replace each selected match with
+    /// - `indices` - The occurrence indices of the matches to replace
+    pub fn replace_indices(query: &'a str, content: &'a str, replacement: &str, indices: &[usize]) -> String {
+        let replacements = Self::new(query, content)
+            .enumerate_matches()
+            .filter(|(index, _)| indices.contains(index))
+            .map(|(_, range)| (range, String::from(replacement)));
+        apply_replacements(content, replacements)
+    }
+
+    /// Wraps each match of the query with the prefix and suffix, such as for terminal highlighting
+    /// or markdown emphasis. Adjacent matches are each wrapped separately, and content without a
+    /// match is returned unchanged.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let result = QueryRangeItr::wrap_matches("needle", "a needle b", "**", "**");
+    /// assert_eq!(result, "a **needle** b");
+    /// ```
+    ///
+    /// **Parameters:**
+    /// - `query` - The search query
+    /// - `content` - The content to look for the query in
+    /// - `prefix` - The text to insert before each match
+    /// - `suffix` - The text to insert after each match
+    pub fn wrap_matches(query: &'a str, content: &'a str, prefix: &str, suffix: &str) -> String {
+        Self::transform_query(query, content, |it| format!("{}{}{}", prefix, it, suffix))
+    }
+
+    /// Computes the list of edits that replace every match of the query with the replacement,
+    /// rather than building the whole new string, such as for minimal editor buffer updates.
+    ///
+    /// The edits are meant to be applied in order: each range refers to the content as it is after
+    /// all the edits before it have been applied, so applying them first to last reproduces a full
+    /// find-and-replace.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::{QueryRangeItr, Edit};
+    ///
+    /// let edits = QueryRangeItr::diff_replace("needle", "needle needle", "pin");
+    /// assert_eq!(edits, vec![
+    ///     Edit { range: 0..6, insert: String::from("pin") },
+    ///     Edit { range: 4..10, insert: String::from("pin") },
+    /// ]);
+    /// ```
+    ///
+    /// **Parameters:**
+    /// - `query` - The search query
+    /// - `content` - The content to look for the query in
+    /// - `replacement` - The text to replace each match with
+    pub fn diff_replace(query: &'a str, content: &'a str, replacement: &str) -> Vec<Edit> {
+        let mut removed: usize = 0;
+        let mut inserted: usize = 0;
+        Self::new(query, content)
+            .map(|range| {
+                let start = range.start - removed + inserted;
+                let edit = Edit { range: start..(start + range.len()), insert: String::from(replacement) };
+                removed += range.len();
+                inserted += replacement.len();
+                edit
+            })
+            .collect()
+    }
+
+    /// Removes all matches of the query from the content in a single pass, such as for redaction,
+    /// returning the stripped content along with each removed match and its range in the original
+    /// content.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let (stripped, removed) = QueryRangeItr::extract_and_strip("secret", "a secret b");
+    /// assert_eq!(stripped, "a  b");
+    /// assert_eq!(removed, vec![(2..8, String::from("secret"))]);
+    /// ```
+    pub fn extract_and_strip(query: &'a str, content: &'a str) -> (String, Vec<(Range<usize>, String)>) {
+        let mut stripped = String::with_capacity(content.len());
+        let mut removed: Vec<(Range<usize>, String)> = Vec::new();
+        let mut last_end: usize = 0;
+        for range in Self::new(query, content) {
+            stripped.push_str(&content[last_end..range.start]);
+            last_end = range.end;
+            removed.push((range.clone(), String::from(&content[range])));
+        }
+        stripped.push_str(&content[last_end..]);
+        (stripped, removed)
+    }
+
+    /// Title-cases each match of the query that begins a sentence, leaving other matches as they
+    /// are. A match begins a sentence when it's at the start of the content (ignoring leading
+    /// whitespace) or follows `.`, `!` or `?` and at least one whitespace character.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let content = "cats purr. cats nap, and so do cats!";
+    /// let result = QueryRangeItr::capitalize_query_at_sentence_start("cats", content);
+    /// assert_eq!(result, "Cats purr. Cats nap, and so do cats!");
+    /// ```
+    pub fn capitalize_query_at_sentence_start(query: &'a str, content: &'a str) -> String {
+        let replacements = Self::new(query, content)
+            .filter(|range| {
+                let before = &content[..range.start];
+                let trimmed = before.trim_end();
+                trimmed.is_empty() || (trimmed.len() < before.len() && trimmed.ends_with(&['.', '!', '?'][..]))
+            })
+            .map(|range| {
+                let replacement = title_case_word(&content[range.clone()]);
+                (range, replacement)
+            });
+        apply_replacements(content, replacements)
+    }
+
+    /// Builds HTML from the content with every match wrapped in `<span class="...">...</span>`.
+    /// All text (matched and non-matched) and the class name are HTML-escaped, so the output is
+    /// safe to inject into a page even when the content contains markup.
+    ///
+    /// **Example:**
+    /// ```
+    /// use query_range::QueryRangeItr;
+    ///
+    /// let result = QueryRangeItr::to_html_highlighted("needle", "<b>needle</b>", "hit");
+    /// assert_eq!(result, "&lt;b&gt;<span class=\"hit\">needle</span>&lt;/b&gt;");
+    /// ```
+    ///
+    /// **Parameters:**
     /// - `query` - The search query
     /// - `content` - The content to look for the query in
-    /// - `transform` -  A transform closure to run on all non-query content
-    pub fn transform_other<T>(
-        query: &'a str,
-        content: &'a str,
-        transform: T,
-    ) -> String where T: Fn(&str) -> String {
-        Self::transform(query, content, transform, true)
+    /// - `class` - The CSS class to give each highlight span
+    pub fn to_html_highlighted(query: &'a str, content: &'a str, class: &str) -> String {
+        let class = escape_html(class);
+        let mut html = String::with_capacity(content.len());
+        Self::for_each_segment(query, content, |segment, is_match| {
+            if is_match {
+                html.push_str(&format!("<span class=\"{}\">{}</span>", class, escape_html(segment)));
+            } else {
+                html.push_str(&escape_html(segment));
+            }
+        });
+        html
+    }
+}
+
+// Iterator implementation ----------------------------------------------------------------------- /
+
+impl<'a> Iterator for QueryRangeItr<'a> {
+    type Item = Range<usize>;
+
+    /// Gets next range of the query in the content.
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(range) = self.peeked.take() {
+            return Some(range);
+        }
+        if let Some(window) = self.window.as_mut() {
+            return window.pop_front();
+        }
+        if self.limit == Some(0) {
+            return None;
+        }
+        let range = if self.inverted {
+            self.next_inverted()
+        } else {
+            self.next_standard()
+        }?;
+        self.count_against_limit();
+        Some(range)
+    }
+
+    /// Gives an upper bound on the remaining ranges (the content length over the query length),
+    /// so that collecting can pre-allocate. The lower bound is always zero.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.max_remaining()))
+    }
+}
+
+/// Once the content is used up, the iterator keeps returning `None`.
+impl<'a> FusedIterator for QueryRangeItr<'a> {}
+
+/// Iterates ranges from the end of the content, yielding the same ranges as forward iteration in
+/// reverse order, whatever the matching options, stride or overlapping mode. A plain query is found
+/// with `rfind` where possible, while other cases scan forward for the last match, which is slower
+/// on long content. With a limit, only the first `k` ranges are yielded from the back too. Forward
+/// and backward calls can be mixed without yielding any range twice or skipping any.
+impl<'a> DoubleEndedIterator for QueryRangeItr<'a> {
+
+    /// Gets the last range of the query in the content.
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.limit.is_some() && self.window.is_none() {
+            self.window = Some(self.by_ref().collect());
+        }
+        if let Some(window) = self.window.as_mut() {
+            let range = window.pop_back();
+            return range.or_else(|| self.peeked.take());
+        }
+        let range = if self.inverted {
+            self.next_back_inverted()
+        } else {
+            self.next_back_standard()
+        };
+        range.or_else(|| self.peeked.take())
+    }
+}
+
+// Tests ----------------------------------------------------------------------------------------- /
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::utility::{to_title_case, repeat_matcher};
+
+    #[test]
+    fn can_iterate_iter() {
+        let query = "needle";
+        let content = "haystackneedlehaystackneedlehaystack";
+        let mut occurrences = QueryRangeItr::new(query, content);
+        while let Some(next) = occurrences.next() {
+            assert_eq!(String::from(&content[next]), "needle");
+        }
+    }
+
+    #[test]
+    fn can_map_iter() {
+        let query = "needle";
+        let content = "haystackneedlehaystackneedlehaystack";
+        let occurrences = QueryRangeItr::new(query, content);
+        let needles: Vec<String> = occurrences.map(|range| String::from(&content[range])).collect();
+        assert_eq!(needles.len(), 2);
+        needles.iter().for_each(|n| assert_eq!(n, "needle"));
+    }
+
+    #[test]
+    fn try_new_rejects_empty_query() {
+        assert_eq!(QueryRangeItr::try_new("", "haystack").err(), Some(QueryRangeError::EmptyQuery));
+        assert_eq!(QueryRangeItr::try_new("", "").err(), Some(QueryRangeError::EmptyQuery));
+        assert_eq!(QueryRangeError::EmptyQuery.to_string(), "query is empty");
+    }
+
+    #[test]
+    fn try_new_rejects_query_longer_than_content() {
+        let error = QueryRangeItr::try_new("haystacks", "haystack").err();
+        assert_eq!(error, Some(QueryRangeError::QueryLongerThanContent));
+        assert_eq!(QueryRangeError::QueryLongerThanContent.to_string(), "query is longer than the content");
+    }
+
+    #[test]
+    fn try_new_iterates_like_new() {
+        let content = "haystackneedlehaystack";
+        let ranges: Vec<Range<usize>> = QueryRangeItr::try_new("needle", content).unwrap().collect();
+        assert_eq!(ranges, vec![8..14]);
+        assert_eq!(QueryRangeItr::try_new("pin", content).unwrap().next(), None);
+    }
+
+    #[test]
+    fn can_iterate_multibyte_query_and_content() {
+        let content = "a🦀b🦀c";
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new("🦀", content).collect();
+        assert_eq!(ranges, vec![1..5, 6..10]);
+        let strings = QueryRangeItr::new_inverted("🦀", content).collect_strings();
+        assert_eq!(strings, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn can_invert_query_next_to_multibyte_chars() {
+        let strings = QueryRangeItr::new_inverted("é", "àéèéé🦀").collect_strings();
+        assert_eq!(strings, vec!["à", "è", "", "🦀"]);
+        let strings = QueryRangeItr::new_inverted("b", "日本b語b").collect_strings();
+        assert_eq!(strings, vec!["日本", "語"]);
+        let strings = QueryRangeItr::new("é", "àéèéé🦀").collect_strings();
+        assert_eq!(strings, vec!["é", "é", "é"]);
+    }
+
+    #[test]
+    fn can_collect_matches() {
+        let content = "Cat cat";
+        let matches = QueryRangeItr::new_case_insensitive("cat", content).collect_matches();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0], Match { range: 0..3, text: String::from("Cat") });
+        assert!(matches.iter().all(|it| content[it.range.clone()] == it.text));
+    }
+
+    #[test]
+    fn can_collect_char_ranges_for_multibyte_content() {
+        let content = "café☕needle café☕needle";
+        let bytes: Vec<Range<usize>> = QueryRangeItr::new("needle", content).collect();
+        let chars = QueryRangeItr::new("needle", content).collect_char_ranges();
+        assert_eq!(bytes, vec![8..14, 23..29]);
+        assert_eq!(chars, vec![5..11, 17..23]);
+        let chars = QueryRangeItr::new_overlapping("éé", "ééé").collect_char_ranges();
+        assert_eq!(chars, vec![0..2, 1..3]);
+    }
+
+    #[test]
+    fn limit_yields_at_most_k_ranges() {
+        let content = "needleXneedleXneedle";
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new("needle", content).limit(1).collect();
+        assert_eq!(ranges, vec![0..6]);
+        assert_eq!(QueryRangeItr::new("needle", content).limit(5).count(), 3);
+        assert_eq!(QueryRangeItr::new("needle", content).limit(0).next(), None);
+        assert_eq!(QueryRangeItr::new_inverted("X", content).limit(2).count(), 2);
+    }
+
+    #[test]
+    fn limit_stops_scanning_after_last_range() {
+        let mut itr = QueryRangeItr::new("needle", "needleXneedleXneedle").limit(1);
+        assert_eq!(itr.next(), Some(0..6));
+        assert_eq!(itr.next(), None);
+        assert_eq!(itr.current_content, "XneedleXneedle");
+        assert_eq!(itr.removed_count, 6);
+    }
+
+    #[test]
+    fn can_get_nth_match() {
+        let content = "needleXneedleXneedle";
+        assert_eq!(QueryRangeItr::nth_match("needle", content, 0), Some(0..6));
+        assert_eq!(QueryRangeItr::nth_match("needle", content, 2), Some(14..20));
+        assert_eq!(QueryRangeItr::nth_match("needle", content, 3), None);
+        assert_eq!(QueryRangeItr::nth_match("pin", content, 0), None);
+    }
+
+    #[test]
+    fn can_check_if_content_contains_query() {
+        assert!(QueryRangeItr::contains("needle", "haystackneedlehaystack"));
+        assert!(!QueryRangeItr::contains("pin", "haystackneedlehaystack"));
+        assert!(QueryRangeItr::contains_case_insensitive("NEEDLE", "haystackneedlehaystack"));
+        assert!(!QueryRangeItr::contains_case_insensitive("pin", "haystackneedlehaystack"));
+    }
+
+    #[test]
+    fn size_hint_upper_bound_covers_match_count() {
+        let itrs = vec![
+            QueryRangeItr::new("needle", "haystackneedlehaystackneedle"),
+            QueryRangeItr::new("aa", "aaaaa"),
+            QueryRangeItr::new("a", "aaaaa"),
+            QueryRangeItr::new_overlapping("aa", "aaaaa"),
+            QueryRangeItr::new_inverted(",", "a,b,,c"),
+            QueryRangeItr::new_inverted(",", ",,,"),
+            QueryRangeItr::new_flexible_ws("a  b", "a b a\nb"),
+            QueryRangeItr::new_tolerating("ab", "a-b ab", '-'),
+            QueryRangeItr::new("", "haystack"),
+        ];
+        for itr in itrs {
+            let (lower, upper) = itr.size_hint();
+            let count = itr.count();
+            assert_eq!(lower, 0);
+            assert!(upper.unwrap() >= count);
+        }
+    }
+
+    #[test]
+    fn size_hint_shrinks_while_iterating() {
+        let mut itr = QueryRangeItr::new("ab", "ababab").limit(2);
+        assert_eq!(itr.size_hint(), (0, Some(2)));
+        itr.next();
+        assert_eq!(itr.size_hint(), (0, Some(1)));
+        itr.next();
+        assert_eq!(itr.size_hint(), (0, Some(0)));
+        assert_eq!(QueryRangeItr::new("ab", "ababab").size_hint(), (0, Some(3)));
+    }
+
+    #[test]
+    fn peek_next_does_not_consume() {
+        let mut itr = QueryRangeItr::new("needle", "needleXneedle");
+        assert_eq!(itr.peek_next(), Some(0..6));
+        assert_eq!(itr.peek_next(), Some(0..6));
+        assert_eq!(itr.size_hint().1, Some(2));
+        assert_eq!(itr.next(), Some(0..6));
+        assert_eq!(itr.peek_next(), Some(7..13));
+        assert_eq!(itr.next(), Some(7..13));
+        assert_eq!(itr.peek_next(), None);
+        assert_eq!(itr.next(), None);
+    }
+
+    #[test]
+    fn peeked_range_is_yielded_last_from_the_back() {
+        let mut itr = QueryRangeItr::new("needle", "needleXneedle").limit(1);
+        assert_eq!(itr.peek_next(), Some(0..6));
+        assert_eq!(itr.next_back(), Some(0..6));
+        assert_eq!(itr.next(), None);
+        let mut itr = QueryRangeItr::new("needle", "needleXneedle").limit(1);
+        assert_eq!(itr.next_back(), Some(0..6));
+        assert_eq!(itr.next(), None);
+        let mut itr = QueryRangeItr::new("needle", "needleXneedle");
+        itr.peek_next();
+        let ranges: Vec<Range<usize>> = itr.rev().collect();
+        assert_eq!(ranges, vec![7..13, 0..6]);
+    }
+
+    #[test]
+    fn limited_reverse_iteration_matches_limited_forward() {
+        for k in 0..6 {
+            let modes = [
+                QueryRangeItr::new("a", "aaaa").limit(k),
+                QueryRangeItr::new_overlapping("aa", "aaaaa").limit(k),
+                QueryRangeItr::new_with_stride("a", "aaaaaaaa", 3).limit(k),
+                QueryRangeItr::new_inverted(",", ",a,,b,").limit(k),
+            ];
+            for itr in modes.iter() {
+                let mut forward: Vec<Range<usize>> = itr.clone().collect();
+                forward.reverse();
+                let backward: Vec<Range<usize>> = itr.clone().rev().collect();
+                assert_eq!(backward, forward, "k: {}, itr: {:?}", k, itr);
+            }
+        }
+    }
+
+    #[test]
+    fn can_interleave_forward_and_backward_iteration_with_limit() {
+        let mut itr = QueryRangeItr::new("a", "aaaa").limit(2);
+        assert_eq!(itr.next_back(), Some(1..2));
+        assert_eq!(itr.next(), Some(0..1));
+        assert_eq!(itr.next(), None);
+        assert_eq!(itr.next_back(), None);
+        let mut itr = QueryRangeItr::new("a", "aaaa").limit(3);
+        assert_eq!(itr.next(), Some(0..1));
+        assert_eq!(itr.size_hint(), (0, Some(2)));
+        assert_eq!(itr.next_back(), Some(2..3));
+        assert_eq!(itr.size_hint(), (0, Some(1)));
+        assert_eq!(itr.next(), Some(1..2));
+        assert_eq!(itr.next_back(), None);
+    }
+
+    #[test]
+    fn cloned_iterator_advances_independently() {
+        let mut itr = QueryRangeItr::new("needle", "needleXneedleXneedleXneedle");
+        itr.next();
+        itr.next();
+        let mut fork = itr.clone();
+        assert_eq!(fork.next(), Some(14..20));
+        let rest: Vec<Range<usize>> = itr.collect();
+        assert_eq!(rest, vec![14..20, 21..27]);
+        assert_eq!(fork.next(), Some(21..27));
+        assert_eq!(fork.next(), None);
+    }
+
+    #[test]
+    fn can_debug_format_iterator() {
+        let debug = format!("{:?}", QueryRangeItr::new("needle", "haystack"));
+        assert!(debug.starts_with("QueryRangeItr"));
+        assert!(debug.contains("\"needle\""));
+    }
+
+    #[test]
+    fn can_count_matches() {
+        assert_eq!(QueryRangeItr::count_matches("needle", "needleXneedle"), 2);
+        assert_eq!(QueryRangeItr::count_matches("aa", "aaaa"), 2);
+        assert_eq!(QueryRangeItr::count_matches("pin", "needleXneedle"), 0);
+    }
+
+    #[test]
+    fn iterator_is_fused() {
+        fn assert_fused<I: FusedIterator>(_: &I) {}
+        let mut itr = QueryRangeItr::new("needle", "needleXneedle");
+        assert_fused(&itr);
+        assert_eq!(itr.by_ref().count(), 2);
+        assert_eq!(itr.next(), None);
+        assert_eq!(itr.next(), None);
+        let mut itr = QueryRangeItr::new_inverted("X", "needleXneedle");
+        assert_eq!(itr.by_ref().count(), 2);
+        assert_eq!(itr.next(), None);
+        assert_eq!(itr.next(), None);
+    }
+
+    #[test]
+    fn can_iterate_in_reverse() {
+        let content = "needlehaystackneedlehaystackneedle";
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new("needle", content).rev().collect();
+        assert_eq!(ranges, vec![28..34, 14..20, 0..6]);
+    }
+
+    #[test]
+    fn can_interleave_forward_and_backward_iteration() {
+        let mut itr = QueryRangeItr::new("needle", "needlehaystackneedle");
+        assert_eq!(itr.next_back(), Some(14..20));
+        assert_eq!(itr.next(), Some(0..6));
+        assert_eq!(itr.next_back(), None);
+        assert_eq!(itr.next(), None);
+        let mut itr = QueryRangeItr::new("needle", "needlehaystackneedle");
+        assert_eq!(itr.next(), Some(0..6));
+        assert_eq!(itr.next_back(), Some(14..20));
+        assert_eq!(itr.next(), None);
+    }
+
+    #[test]
+    fn reverse_inverted_mirrors_forward_inverted() {
+        for content in ["a,b,c", ",a,,b,", ",", "", "abc", "a,,"].iter() {
+            let forward: Vec<Range<usize>> = QueryRangeItr::new_inverted(",", content).collect();
+            let mut backward: Vec<Range<usize>> = QueryRangeItr::new_inverted(",", content).rev().collect();
+            backward.reverse();
+            assert_eq!(backward, forward, "content: {:?}", content);
+        }
+    }
+
+    #[test]
+    fn reverse_iteration_matches_forward_in_every_mode() {
+        for content in ["aaaaaa", "aaa", "abababa", "aéaéaéa", "", "xaax"].iter() {
+            let modes = [
+                ("plain", QueryRangeItr::new("aa", content)),
+                ("self-overlapping", QueryRangeItr::new("aba", content)),
+                ("stride", QueryRangeItr::new_with_stride("a", content, 4)),
+                ("overlapping", QueryRangeItr::new_overlapping("aa", content)),
+                ("overlapping multibyte", QueryRangeItr::new_overlapping("aéa", content)),
+                ("inverted", QueryRangeItr::new_inverted("aa", content)),
+            ];
+            for (mode, itr) in modes.iter() {
+                let forward: Vec<Range<usize>> = itr.clone().collect();
+                let mut backward: Vec<Range<usize>> = itr.clone().rev().collect();
+                backward.reverse();
+                assert_eq!(backward, forward, "mode: {}, content: {:?}", mode, content);
+            }
+        }
+    }
+
+    #[test]
+    fn can_interleave_stride_and_overlapping_iteration() {
+        let mut itr = QueryRangeItr::new_overlapping("aa", "aaaa");
+        assert_eq!(itr.next_back(), Some(2..4));
+        assert_eq!(itr.next(), Some(0..2));
+        assert_eq!(itr.next_back(), Some(1..3));
+        assert_eq!(itr.next(), None);
+        let mut itr = QueryRangeItr::new_with_stride("a", "aaaaaa", 4);
+        assert_eq!(itr.next_back(), Some(4..5));
+        assert_eq!(itr.next(), Some(0..1));
+        assert_eq!(itr.next_back(), None);
+    }
+
+    #[test]
+    fn reverse_iteration_respects_matching_options() {
+        let content = "Cat cat CAT";
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_case_insensitive("cat", content).rev().collect();
+        assert_eq!(ranges, vec![8..11, 4..7, 0..3]);
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_word_suffix("ing", "singing ingest").rev().collect();
+        assert_eq!(ranges, vec![4..7]);
+    }
+
+    #[test]
+    fn can_collect_strings() {
+        let query = "needle";
+        let content = "haystackneedlehaystackneedlehaystack";
+        let mut occurrences = QueryRangeItr::new(query, content);
+        let needles: Vec<String> = occurrences.collect_strings();
+        assert_eq!(needles.len(), 2);
+        needles.iter().for_each(|n| assert_eq!(n, "needle"));
+    }
+
+    #[test]
+    fn extracted_strings_are_sorted() {
+        let content = "haystackneedlehaystackneedle";
+        assert_eq!(QueryRangeItr::extract_sorted("needle", content), vec!["needle", "needle"]);
+        assert!(QueryRangeItr::extract_sorted("pin", content).is_empty());
+        let sorted = QueryRangeItr::new_case_insensitive("ab", "ab-aB AB.Ab ab").collect_sorted_strings();
+        assert_eq!(sorted, vec!["AB", "Ab", "aB", "ab", "ab"]);
+    }
+
+    #[test]
+    fn case_insensitive_matches_mixed_case() {
+        let content = "haystackNEEDLEhaystackneedleNeEdLe";
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_case_insensitive("nEEdle", content).collect();
+        assert_eq!(ranges, vec![8..14, 22..28, 28..34]);
+        assert!(QueryRangeItr::new("Needle", content).next().is_none());
+    }
+
+    #[test]
+    fn case_insensitive_only_folds_ascii() {
+        let strings = QueryRangeItr::new_case_insensitive("CAFÉ", "café CAFÉ Café").collect_strings();
+        assert_eq!(strings, vec!["CAFÉ"]);
+    }
+
+    #[test]
+    fn can_invert_case_insensitive_matches() {
+        let strings = QueryRangeItr::new_inverted_case_insensitive(",AND,", "a,and,b,AND,c").collect_strings();
+        assert_eq!(strings, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn can_match_with_padded_query_when_trimmed() {
+        let query = " needle\t";
+        let content = "haystackneedlehaystackneedlehaystack";
+        assert_eq!(QueryRangeItr::new(query, content).count(), 0);
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_trimmed(query, content).collect();
+        assert_eq!(ranges, vec![8..14, 22..28]);
+    }
+
+    #[test]
+    fn word_suffix_matches_only_at_word_ends() {
+        let content = "running ingest sing";
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_word_suffix("ing", content).collect();
+        assert_eq!(ranges, vec![4..7, 16..19]);
+        assert_eq!(QueryRangeItr::new("ing", content).count(), 3);
+    }
+
+    #[test]
+    fn word_suffix_does_not_match_word_start() {
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_word_suffix("ing", "ingest").collect();
+        assert!(ranges.is_empty());
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_word_suffix(".rs", "lib.rs, main.rsx").collect();
+        assert_eq!(ranges, vec![3..6]);
+    }
+
+    #[test]
+    fn enumerated_match_indices_increment_from_zero() {
+        let content = "needle haystack needle haystack needle";
+        let indices: Vec<usize> = QueryRangeItr::new("needle", content)
+            .enumerate_matches()
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(indices, vec![0, 1, 2]);
+        let indexed: Vec<(usize, Range<usize>)> = QueryRangeItr::new("needle", content).enumerate_matches().collect();
+        assert_eq!(indexed[2], (2, 32..38));
+    }
+
+    #[test]
+    fn into_strings_yields_lazily() {
+        let content = "needle1needle2needle3";
+        let mut strings = QueryRangeItr::new("needle", content).into_strings();
+        let first: Vec<String> = strings.by_ref().take(1).collect();
+        assert_eq!(first, vec!["needle"]);
+        let rest: Vec<String> = strings.collect();
+        assert_eq!(rest, vec!["needle", "needle"]);
+    }
+
+    #[test]
+    fn unique_strings_keep_first_seen_order() {
+        let unique = QueryRangeItr::new_inverted(" ", "dog cat dog bird cat").unique_strings();
+        assert_eq!(unique, vec!["dog", "cat", "bird"]);
+        let unique = QueryRangeItr::new("needle", "needlehaystackneedle").unique_strings();
+        assert_eq!(unique, vec!["needle"]);
+    }
+
+    #[test]
+    fn unique_strings_keep_each_casing_when_case_insensitive() {
+        let unique = QueryRangeItr::new_case_insensitive("cat", "Cat cat CAT cat").unique_strings();
+        assert_eq!(unique, vec!["Cat", "cat", "CAT"]);
+    }
+
+    #[test]
+    fn can_pair_ranges_with_matched_slices() {
+        let matches: Vec<(Range<usize>, &str)> = QueryRangeItr::new("needle", "xneedleyneedle").with_str().collect();
+        assert_eq!(matches, vec![(1..7, "needle"), (8..14, "needle")]);
+        let matches: Vec<(Range<usize>, &str)> = QueryRangeItr::new_inverted("needle", "xneedleyneedle").with_str().collect();
+        assert_eq!(matches, vec![(0..1, "x"), (7..8, "y")]);
+    }
+
+    #[test]
+    fn can_get_byte_and_char_lengths() {
+        let content = "needle☕café☕needle";
+        let lengths: Vec<(Range<usize>, usize, usize)> = QueryRangeItr::new("☕café☕", content).with_lengths().collect();
+        assert_eq!(lengths, vec![(6..17, 11, 6)]);
+        let lengths: Vec<(Range<usize>, usize, usize)> = QueryRangeItr::new("needle", content).with_lengths().collect();
+        assert_eq!(lengths, vec![(0..6, 6, 6), (17..23, 6, 6)]);
+    }
+
+    #[test]
+    fn remaining_bytes_decrease_monotonically() {
+        let content = "haystackneedlehaystackneedlehaystackneedle";
+        let remaining: Vec<usize> = QueryRangeItr::new("needle", content)
+            .with_progress()
+            .map(|(_, remaining)| remaining)
+            .collect();
+        assert_eq!(remaining, vec![28, 14, 0]);
+        assert!(remaining.windows(2).all(|pair| pair[0] > pair[1]));
+    }
+
+    #[test]
+    fn can_get_utf16_ranges_around_surrogate_pairs() {
+        let content = "a🦀needle🦀🦀needle";
+        let bytes: Vec<Range<usize>> = QueryRangeItr::new("needle", content).collect();
+        let utf16: Vec<Range<usize>> = QueryRangeItr::new("needle", content).utf16_ranges().collect();
+        assert_eq!(bytes, vec![5..11, 19..25]);
+        assert_eq!(utf16, vec![3..9, 13..19]);
+        assert_eq!(content[..19].chars().count(), 10);
+    }
+
+    #[test]
+    fn whole_word_skips_matches_inside_words() {
+        let content = "category concatenate cat";
+        assert_eq!(QueryRangeItr::new_whole_word("cat", content).count(), 1);
+        assert_eq!(QueryRangeItr::new("cat", content).count(), 3);
+    }
+
+    #[test]
+    fn whole_word_matches_at_content_edges() {
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_whole_word("cat", "cat, cat").collect();
+        assert_eq!(ranges, vec![0..3, 5..8]);
+    }
+
+    #[test]
+    fn whole_word_treats_accented_letters_as_word_chars() {
+        assert_eq!(QueryRangeItr::new_whole_word("caf", "café écaf caf").collect_strings(), vec!["caf"]);
+        assert_eq!(QueryRangeItr::new_whole_word("caf", "café écaf").count(), 0);
+    }
+
+    #[test]
+    fn whole_word_with_punctuated_query() {
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_whole_word("c++", "c++ vs xc++ vs c++x").collect();
+        assert_eq!(ranges, vec![0..3]);
+    }
+
+    #[test]
+    fn escaped_matches_are_ignored() {
+        let content = "a,b\\,c";
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_with_escape(",", content, '\\').collect();
+        assert_eq!(ranges, vec![1..2]);
+        assert_eq!(QueryRangeItr::new(",", content).count(), 2);
+    }
+
+    #[test]
+    fn doubled_escape_does_not_escape_match() {
+        let content = "a\\\\,b\\\\\\,c";
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_with_escape(",", content, '\\').collect();
+        assert_eq!(ranges, vec![3..4]);
+    }
+
+    #[test]
+    fn flexible_ws_matches_varied_whitespace() {
+        let content = "hello world, hello\n  world, hello\tworld, helloworld";
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_flexible_ws("hello world", content).collect();
+        assert_eq!(ranges, vec![0..11, 13..26, 28..39]);
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_flexible_ws("  a   b c ", "a\nb  c").collect();
+        assert_eq!(ranges, vec![0..6]);
+    }
+
+    #[test]
+    fn flexible_ws_requires_whitespace_between_words() {
+        assert_eq!(QueryRangeItr::new_flexible_ws("hello world", "helloworld").count(), 0);
+        assert_eq!(QueryRangeItr::new_flexible_ws("hello world", "hello wor").count(), 0);
+        assert_eq!(QueryRangeItr::new_flexible_ws(" ", "a b").count(), 0);
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_flexible_ws("a b", "a a  b").collect();
+        assert_eq!(ranges, vec![2..6]);
+    }
+
+    #[test]
+    fn tolerating_matches_with_and_without_separator() {
+        let content = "email, e-mail, e–mail";
+        let strings = QueryRangeItr::new_tolerating("email", content, '-').collect_strings();
+        assert_eq!(strings, vec!["email", "e-mail"]);
+    }
+
+    #[test]
+    fn overlapping_finds_overlapping_matches() {
+        assert_eq!(QueryRangeItr::new_overlapping("aa", "aaaa").count(), 3);
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_overlapping("aba", "ababa").collect();
+        assert_eq!(ranges, vec![0..3, 2..5]);
+    }
+
+    #[test]
+    fn non_overlapping_default_is_unchanged() {
+        assert_eq!(QueryRangeItr::new("aa", "aaaa").count(), 2);
+        assert_eq!(QueryRangeItr::new("aba", "ababa").count(), 1);
+    }
+
+    #[test]
+    fn overlapping_advances_by_char() {
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_overlapping("éé", "ééé").collect();
+        assert_eq!(ranges, vec![0..4, 2..6]);
+    }
+
+    #[test]
+    fn large_stride_thins_out_dense_matches() {
+        let content = "ab ab ab ab ab ab ab ab";
+        let dense: Vec<Range<usize>> = QueryRangeItr::new_with_stride("ab", content, 0).collect();
+        let thinned: Vec<Range<usize>> = QueryRangeItr::new_with_stride("ab", content, 7).collect();
+        assert_eq!(dense.len(), 8);
+        assert_eq!(thinned, vec![0..2, 9..11, 18..20]);
+    }
+
+    #[test]
+    fn stride_snaps_to_char_boundary() {
+        let thinned: Vec<Range<usize>> = QueryRangeItr::new_with_stride("a", "aéa", 2).collect();
+        assert_eq!(thinned, vec![0..1, 3..4]);
+    }
+
+    #[test]
+    fn huge_stride_does_not_overflow() {
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_with_stride("a", "ba", usize::MAX).collect();
+        assert_eq!(ranges, vec![1..2]);
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_with_stride("a", "aaa", usize::MAX).collect();
+        assert_eq!(ranges, vec![0..1]);
+    }
+
+    #[test]
+    fn can_get_gap_before_each_match() {
+        let query = "needle";
+        let content = "haystackneedlehaystackneedlehaystack";
+        let gaps: Vec<(usize, Range<usize>)> = QueryRangeItr::new(query, content).with_gap_before().collect();
+        assert_eq!(gaps[0].0, "haystack".len());
+        assert_eq!(gaps, vec![(8, 8..14), (8, 22..28)]);
     }
 
-    /// Reassembles content, but transforms both the query content and the non-query content
-    ///
-    /// **Example:**
-    /// ```
-    /// use query_range::QueryRangeItr;
-    /// use query_range::utility::to_title_case;
-    ///
-    /// let query = "needle";
-    /// let content = "haystackneedlehaystackneedlehaystack";
-    /// let result = QueryRangeItr::transform_all(
-    ///     query,
-    ///     content,
-    ///     |it| it.to_uppercase(),
-    ///     |it| to_title_case(it),
-    /// );
-    /// assert_eq!(result, "HaystackNEEDLEHaystackNEEDLEHaystack");
-    /// ```
-    ///
-    /// **Parameters:**
-    /// - `query` - The search query
-    /// - `content` - The content to look for the query in
-    /// - `transform_query` -  A transform closure to run on all query content
-    /// - `transform_non_query` - If `true`, applies the transform to the non-query content
-    pub fn transform_all<TQ, TNQ>(
-        query: &'a str,
-        content: &'a str,
-        transform_query: TQ,
-        transform_non_query: TNQ,
-    ) -> String
-        where
-            TQ: Fn(&str) -> String,
-            TNQ: Fn(&str) -> String,
-    {
-        let transformed_content = Self::transform(query, content, &transform_query, false);
-        let transformed_query = transform_query(query);
-        let transformed = QueryRangeItr::transform(&transformed_query, &transformed_content, transform_non_query, true);
-        transformed
+    #[test]
+    fn gap_before_is_zero_for_adjacent_matches() {
+        let gaps: Vec<usize> = QueryRangeItr::new("ab", "ababxab")
+            .with_gap_before()
+            .map(|(gap, _)| gap)
+            .collect();
+        assert_eq!(gaps, vec![0, 0, 1]);
     }
-}
 
-// Iterator implementation ----------------------------------------------------------------------- /
+    #[test]
+    fn while_dense_stops_at_large_gap() {
+        let content = "xxxxneedle-needle-needle----------needle-needle";
+        let ranges: Vec<Range<usize>> = QueryRangeItr::while_dense("needle", content, 3).collect();
+        assert_eq!(ranges, vec![4..10, 11..17, 18..24]);
+        assert_eq!(QueryRangeItr::while_dense("needle", content, 10).count(), 5);
+    }
 
-impl<'a> Iterator for QueryRangeItr<'a> {
-    type Item = Range<usize>;
+    #[test]
+    fn can_flag_word_boundaries() {
+        let content = "cat in concatenate";
+        let flagged: Vec<(Range<usize>, bool)> = QueryRangeItr::with_word_boundary_flag("cat", content).collect();
+        assert_eq!(flagged, vec![(0..3, true), (10..13, false)]);
+    }
 
-    /// Gets next range of the query in the content.
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.inverted {
-            self.next_inverted()
-        } else {
-            self.next_standard()
+    #[test]
+    fn multi_merged_combines_overlapping_keywords() {
+        let content = "xfoobarx barfoo";
+        let spans: Vec<Range<usize>> = QueryRangeItr::new_multi_merged(&["foobar", "bar", "foo"], content).collect();
+        assert_eq!(spans, vec![1..7, 9..15]);
+    }
+
+    #[test]
+    fn multi_merged_keeps_disjoint_matches_in_order() {
+        let content = "cat dog cat";
+        let spans: Vec<Range<usize>> = QueryRangeItr::new_multi_merged(&["dog", "cat"], content).collect();
+        assert_eq!(spans, vec![0..3, 4..7, 8..11]);
+        assert_eq!(QueryRangeItr::new_multi_merged(&[], content).count(), 0);
+    }
+
+    #[test]
+    fn multi_prefers_earliest_then_longest_match() {
+        let matches: Vec<_> = QueryRangeItr::new_multi(&["cat", "category"], "categorical cat").collect();
+        assert_eq!(matches, vec![(0, 0..3), (0, 12..15)]);
+        let matches: Vec<_> = QueryRangeItr::new_multi(&["cat", "category"], "category cat").collect();
+        assert_eq!(matches, vec![(1, 0..8), (0, 9..12)]);
+    }
+
+    #[test]
+    fn multi_prefers_earliest_start_over_length() {
+        let matches: Vec<_> = QueryRangeItr::new_multi(&["gorical", "ego"], "categorical").collect();
+        assert_eq!(matches, vec![(1, 3..6)]);
+    }
+
+    #[test]
+    fn tie_break_first_query_prefers_lower_index() {
+        let queries = ["ab", "abc", "a"];
+        let matches: Vec<_> = QueryRangeItr::new_multi_with(&queries, "abcd abc", TieBreak::FirstQuery).collect();
+        assert_eq!(matches, vec![(0, 0..2), (0, 5..7)]);
+    }
+
+    #[test]
+    fn tie_break_longest_match_prefers_longest() {
+        let queries = ["ab", "abc", "a"];
+        let matches: Vec<_> = QueryRangeItr::new_multi_with(&queries, "abcd abc", TieBreak::LongestMatch).collect();
+        assert_eq!(matches, vec![(1, 0..3), (1, 5..8)]);
+    }
+
+    #[test]
+    fn tie_break_shortest_match_prefers_shortest() {
+        let queries = ["ab", "abc", "a"];
+        let matches: Vec<_> = QueryRangeItr::new_multi_with(&queries, "abcd abc", TieBreak::ShortestMatch).collect();
+        assert_eq!(matches, vec![(2, 0..1), (2, 5..6)]);
+    }
+
+    #[test]
+    fn tie_break_equal_lengths_prefer_lower_index() {
+        let queries = ["ab", "xy", "ab"];
+        let matches: Vec<_> = QueryRangeItr::new_multi_with(&queries, "xyab", TieBreak::LongestMatch).collect();
+        assert_eq!(matches, vec![(1, 0..2), (0, 2..4)]);
+    }
+
+    #[test]
+    fn can_find_repeated_runs() {
+        let content = "ab-ab-abXab";
+        let runs: Vec<Range<usize>> = QueryRangeItr::new_repeated("ab", "-", content).collect();
+        assert_eq!(runs, vec![0..8, 9..11]);
+    }
+
+    #[test]
+    fn repeated_runs_exclude_trailing_separator() {
+        let content = "1, 1, 1, x 1,";
+        let runs: Vec<Range<usize>> = QueryRangeItr::new_repeated("1", ", ", content).collect();
+        assert_eq!(runs, vec![0..7, 11..12]);
+        assert_eq!(QueryRangeItr::new_repeated("ab", "-", "xyz").count(), 0);
+    }
+
+    #[test]
+    fn balanced_groups_include_nesting() {
+        let content = "(a(b)c) x (d) ((e)(f))";
+        let groups: Vec<&str> = QueryRangeItr::new_balanced('(', ')', content)
+            .map(|range| &content[range])
+            .collect();
+        assert_eq!(groups, vec!["(a(b)c)", "(d)", "((e)(f))"]);
+        let content = "«a«b»»";
+        let groups: Vec<Range<usize>> = QueryRangeItr::new_balanced('«', '»', content).collect();
+        assert_eq!(groups, vec![0..content.len()]);
+    }
+
+    #[test]
+    fn balanced_groups_handle_unbalanced_input() {
+        let content = ") (a)) (b (c)";
+        let groups: Vec<&str> = QueryRangeItr::new_balanced('(', ')', content)
+            .map(|range| &content[range])
+            .collect();
+        assert_eq!(groups, vec!["(a)"]);
+        assert_eq!(QueryRangeItr::new_balanced('(', ')', "no groups").count(), 0);
+    }
+
+    #[test]
+    fn balanced_groups_pair_identical_delimiters() {
+        let content = r#"say "hi" and "bye" then "oops"#;
+        let groups: Vec<&str> = QueryRangeItr::new_balanced('"', '"', content)
+            .map(|range| &content[range])
+            .collect();
+        assert_eq!(groups, vec![r#""hi""#, r#""bye""#]);
+    }
+
+    #[test]
+    fn can_get_gaps_between_matches() {
+        let content = "haystackneedlehaystackneedlehaystack";
+        let gaps: Vec<usize> = QueryRangeItr::gaps("needle", content).collect();
+        assert_eq!(gaps, vec![8]);
+        let gaps: Vec<usize> = QueryRangeItr::gaps("ab", "abab-ab---ab").collect();
+        assert_eq!(gaps, vec![0, 1, 3]);
+        assert_eq!(QueryRangeItr::gaps("needle", "needle").count(), 0);
+    }
+
+    #[test]
+    fn can_count_matches_by_case() {
+        let counts = QueryRangeItr::count_by_case_insensitive("cat", "Cat cat CAT");
+        let mut expected: HashMap<String, usize> = HashMap::new();
+        expected.insert(String::from("Cat"), 1);
+        expected.insert(String::from("cat"), 1);
+        expected.insert(String::from("CAT"), 1);
+        assert_eq!(counts, expected);
+    }
+
+    #[test]
+    fn contexts_are_clamped_at_content_edges() {
+        let content = "xneedle haystack needley";
+        let windows = QueryRangeItr::contexts("needle", content, 4);
+        assert_eq!(windows, vec![0..11, 13..24]);
+    }
+
+    #[test]
+    fn contexts_count_radius_in_chars() {
+        let content = "ééé needle ééé";
+        let windows = QueryRangeItr::contexts("needle", content, 2);
+        assert_eq!(&content[windows[0].clone()], "é needle é");
+    }
+
+    #[test]
+    fn contexts_of_nearby_matches_are_not_merged() {
+        let windows = QueryRangeItr::contexts("ab", "ab-ab", 2);
+        assert_eq!(windows, vec![0..4, 1..5]);
+    }
+
+    #[test]
+    fn can_get_snippets() {
+        let content = "haystackneedlehaystackneedlehaystack";
+        let snippets: Vec<Snippet> = QueryRangeItr::snippets("needle", content, 4).collect();
+        assert_eq!(snippets.len(), 2);
+        assert_eq!(snippets[0].text, "tackneedlehays");
+        assert_eq!(snippets[0].match_in_snippet, 4..10);
+        snippets.iter().for_each(|snippet| assert_eq!(snippet.matched(), "needle"));
+    }
+
+    #[test]
+    fn snippets_are_clamped_at_content_edges() {
+        let content = "needle☕éneedle";
+        let snippets: Vec<Snippet> = QueryRangeItr::snippets("needle", content, 3).collect();
+        assert_eq!(snippets[0].text, "needle☕én");
+        assert_eq!(snippets[0].match_in_snippet, 0..6);
+        assert_eq!(snippets[1].text, "e☕éneedle");
+        assert_eq!(snippets[1].match_in_snippet, 6..12);
+    }
+
+    #[test]
+    fn fuzzy_ranked_puts_exact_match_first() {
+        let content = "nedxle haystack needle";
+        let ranked = QueryRangeItr::fuzzy_ranked("needle", content, 2);
+        assert_eq!(ranked[0], (16..22, 0));
+        assert_eq!(ranked[1], (0..6, 2));
+        assert_eq!(ranked.len(), 2);
+    }
+
+    #[test]
+    fn fuzzy_ranked_respects_max_distance() {
+        let content = "nedxle haystack needle";
+        assert_eq!(QueryRangeItr::fuzzy_ranked("needle", content, 0), vec![(16..22, 0)]);
+        assert!(QueryRangeItr::fuzzy_ranked("", content, 2).is_empty());
+        assert!(QueryRangeItr::fuzzy_ranked("needle", "", 2).is_empty());
+    }
+
+    #[test]
+    fn can_split_once() {
+        assert_eq!(QueryRangeItr::split_once("=", "a=b=c"), Some(("a", "b=c")));
+        assert_eq!(QueryRangeItr::split_once("needle", "haystackneedlehaystack"), Some(("haystack", "haystack")));
+    }
+
+    #[test]
+    fn split_once_is_none_without_match() {
+        assert_eq!(QueryRangeItr::split_once("=", "abc"), None);
+    }
+
+    #[test]
+    fn split_once_with_match_at_start_or_end() {
+        assert_eq!(QueryRangeItr::split_once("=", "=abc"), Some(("", "abc")));
+        assert_eq!(QueryRangeItr::split_once("=", "abc="), Some(("abc", "")));
+    }
+
+    #[test]
+    fn segments_tile_the_content() {
+        let segments = QueryRangeItr::segments("ab", "abxab");
+        assert_eq!(segments, vec![Segment::Match(0..2), Segment::Other(2..3), Segment::Match(3..5)]);
+        assert_eq!(QueryRangeItr::segments("ab", ""), vec![]);
+    }
+
+    #[test]
+    fn can_split_like_str_split() {
+        let segments: Vec<&str> = QueryRangeItr::split("needle", "needleAneedleBneedle").collect();
+        assert_eq!(segments, vec!["", "A", "B", ""]);
+        let segments: Vec<&str> = QueryRangeItr::split(",", "a,,b").collect();
+        assert_eq!(segments, vec!["a", "", "b"]);
+        let cases = [("needle", "needleAneedleBneedle"), (",", "a,,b"), ("ab", "abab"), (",", "")];
+        for (query, content) in cases.iter() {
+            let expected: Vec<&str> = content.split(*query).collect();
+            assert_eq!(QueryRangeItr::split(query, content).collect::<Vec<_>>(), expected);
         }
     }
-}
 
-// Tests ----------------------------------------------------------------------------------------- /
+    #[test]
+    fn split_without_match_yields_whole_content() {
+        assert_eq!(QueryRangeItr::split("pin", "haystack").collect::<Vec<_>>(), vec!["haystack"]);
+        assert_eq!(QueryRangeItr::split("", "haystack").collect::<Vec<_>>(), vec!["haystack"]);
+        assert_eq!(QueryRangeItr::split("pin", "").collect::<Vec<_>>(), vec![""]);
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use super::super::utility::to_title_case;
+    #[test]
+    fn can_split_inclusive() {
+        let segments: Vec<&str> = QueryRangeItr::split_inclusive(",", "a,b,c").collect();
+        assert_eq!(segments, vec!["a,", "b,", "c"]);
+        let segments: Vec<&str> = QueryRangeItr::split_inclusive("needle", "haystackneedlehay").collect();
+        assert_eq!(segments, vec!["haystackneedle", "hay"]);
+    }
 
     #[test]
-    fn can_iterate_iter() {
-        let query = "needle";
+    fn split_inclusive_with_trailing_or_missing_delimiter() {
+        let segments: Vec<&str> = QueryRangeItr::split_inclusive(",", "a,,b,").collect();
+        assert_eq!(segments, vec!["a,", ",", "b,"]);
+        let segments: Vec<&str> = QueryRangeItr::split_inclusive(",", "abc").collect();
+        assert_eq!(segments, vec!["abc"]);
+        assert_eq!(QueryRangeItr::split_inclusive(",", "").count(), 0);
+    }
+
+    #[test]
+    fn can_stream_matches_to_another_thread() {
+        use std::sync::mpsc::channel;
+        use std::thread;
+
+        let (tx, rx) = channel();
+        let producer = thread::spawn(move || {
+            let content = "haystackneedlehaystackneedlehaystack";
+            QueryRangeItr::stream_to("needle", content, tx)
+        });
+        let ranges: Vec<Range<usize>> = rx.iter().collect();
+        assert!(producer.join().unwrap().is_ok());
+        assert_eq!(ranges, vec![8..14, 22..28]);
+    }
+
+    #[test]
+    fn stream_to_stops_when_receiver_hangs_up() {
+        use std::sync::mpsc::channel;
+
+        let (tx, rx) = channel();
+        drop(rx);
+        assert!(QueryRangeItr::stream_to("needle", "needleneedle", tx).is_err());
+    }
+
+    #[test]
+    fn can_convert_ranges_to_u32() {
         let content = "haystackneedlehaystackneedlehaystack";
-        let mut occurrences = QueryRangeItr::new(query, content);
-        while let Some(next) = occurrences.next() {
-            assert_eq!(String::from(&content[next]), "needle");
+        let ranges: Vec<Result<Range<u32>, TryFromIntError>> = QueryRangeItr::new("needle", content)
+            .ranges_as::<u32>()
+            .collect();
+        assert_eq!(ranges, vec![Ok(8..14), Ok(22..28)]);
+    }
+
+    #[test]
+    fn ranges_as_errors_when_offset_does_not_fit() {
+        let content = format!("{}needle", "x".repeat(300));
+        let mut ranges = QueryRangeItr::new("needle", &content).ranges_as::<u8>();
+        assert!(ranges.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn can_reconstruct_content_by_visiting_segments() {
+        use std::cell::RefCell;
+
+        let content = "needlehaystackneedlehaystackneedlehaystack";
+        let rebuilt = RefCell::new(String::new());
+        let mut matches: Vec<&str> = Vec::new();
+        QueryRangeItr::visit_segments(
+            "needle",
+            content,
+            |found| {
+                matches.push(found);
+                rebuilt.borrow_mut().push_str(found);
+            },
+            |other| rebuilt.borrow_mut().push_str(other),
+        );
+        assert_eq!(rebuilt.into_inner(), content);
+        assert_eq!(matches, vec!["needle", "needle", "needle"]);
+    }
+
+    /// The original two-pass `transform`, kept to check the streaming version against.
+    fn sorted_transform<T>(query: &str, content: &str, transform: T, invert: bool) -> String
+        where T: Fn(&str) -> String
+    {
+        let selects = QueryRangeItr::new_base(query, content, invert);
+        let non_selects = QueryRangeItr::new_base(query, content, !invert);
+        let mut merged: Vec<(String, usize)> = selects
+            .map(|range| (transform(&content[range.clone()]), range.start))
+            .collect();
+        merged.extend(non_selects.map(|range| (String::from(&content[range.clone()]), range.start)));
+        merged.sort_by_key(|sub| sub.1);
+        merged.into_iter().map(|sub| sub.0).collect()
+    }
+
+    #[test]
+    fn streaming_transform_equals_sorted_transform() {
+        let content = "needle".repeat(2000) + &"hayneedle".repeat(2000) + "hay";
+        let mark = |it: &str| format!("<{}>", it.to_uppercase());
+        for invert in [false, true].iter() {
+            let expected = sorted_transform("needle", &content, mark, *invert);
+            assert_eq!(QueryRangeItr::transform("needle", &content, mark, *invert), expected);
+        }
+        for content in ["", "needle", "hay", "needlehay", "hayneedle"].iter() {
+            for invert in [false, true].iter() {
+                let expected = sorted_transform("needle", content, mark, *invert);
+                assert_eq!(QueryRangeItr::transform("needle", content, mark, *invert), expected);
+            }
         }
     }
 
     #[test]
-    fn can_map_iter() {
+    fn transform_cow_borrows_without_matches() {
+        let content = "haystackhaystack";
+        let result = QueryRangeItr::transform_cow("needle", content, |it| it.to_uppercase(), false);
+        assert!(matches!(result, Cow::Borrowed(_)));
+        assert_eq!(result, content);
+        let result = QueryRangeItr::transform_cow("needle", "", |it| it.to_uppercase(), true);
+        assert!(matches!(result, Cow::Borrowed("")));
+    }
+
+    #[test]
+    fn transform_cow_owns_transformed_content() {
+        let result = QueryRangeItr::transform_cow("needle", "hayneedle", |it| it.to_uppercase(), false);
+        assert!(matches!(result, Cow::Owned(_)));
+        assert_eq!(result, "hayNEEDLE");
+        let result = QueryRangeItr::transform_cow("needle", "haystack", |it| it.to_uppercase(), true);
+        assert_eq!(result, "HAYSTACK");
+    }
+
+    #[test]
+    fn transform_all_does_not_mix_up_transformed_query_with_content() {
+        let result = QueryRangeItr::transform_all("a", "bab", |_| String::from("b"), |it| it.to_uppercase());
+        assert_eq!(result, "BbB");
+        let result = QueryRangeItr::transform_all("a", "bab", |it| it.to_uppercase(), |it| it.to_uppercase());
+        assert_eq!(result, "BAB");
+        let result = QueryRangeItr::transform_all("a", "aba", |it| format!("[{}]", it), |it| format!("({})", it));
+        assert_eq!(result, "()[a](b)[a]");
+    }
+
+    #[test]
+    fn can_transform_query() {
         let query = "needle";
         let content = "haystackneedlehaystackneedlehaystack";
-        let occurrences = QueryRangeItr::new(query, content);
-        let needles: Vec<String> = occurrences.map(|range| String::from(&content[range])).collect();
-        assert_eq!(needles.len(), 2);
-        needles.iter().for_each(|n| assert_eq!(n, "needle"));
+        let result = QueryRangeItr::transform_query(query, content, |it| it.to_uppercase());
+        assert_eq!(result, "haystackNEEDLEhaystackNEEDLEhaystack");
     }
 
     #[test]
-    fn can_collect_strings() {
+    fn can_transform_with_occurrence_index() {
+        let result = QueryRangeItr::transform_indexed("needle", "xneedleyneedle", |index, _| format!("[{}]", index));
+        assert_eq!(result, "x[0]y[1]");
+        let result = QueryRangeItr::transform_indexed("needle", "haystack", |index, _| format!("[{}]", index));
+        assert_eq!(result, "haystack");
+    }
+
+    #[test]
+    fn can_truncate_transform_within_match() {
         let query = "needle";
         let content = "haystackneedlehaystackneedlehaystack";
-        let mut occurrences = QueryRangeItr::new(query, content);
-        let needles: Vec<String> = occurrences.collect_strings();
-        assert_eq!(needles.len(), 2);
-        needles.iter().for_each(|n| assert_eq!(n, "needle"));
+        let result = QueryRangeItr::transform_truncated(query, content, |it| it.to_uppercase(), 10);
+        assert_eq!(result, "haystackNE…");
+        let result = QueryRangeItr::transform_truncated(query, content, |_| String::from("ñéé"), 10);
+        assert_eq!(result, "haystackñé…");
     }
 
     #[test]
-    fn can_transform_query() {
+    fn can_truncate_transform_between_matches() {
         let query = "needle";
         let content = "haystackneedlehaystackneedlehaystack";
-        let result = QueryRangeItr::transform_query(query, content, |it| it.to_uppercase());
-        assert_eq!(result, "haystackNEEDLEhaystackNEEDLEhaystack");
+        let result = QueryRangeItr::transform_truncated(query, content, |it| it.to_uppercase(), 17);
+        assert_eq!(result, "haystackNEEDLEhay…");
+    }
+
+    #[test]
+    fn transform_truncated_leaves_short_output_untouched() {
+        let query = "needle";
+        let content = "haystackneedle";
+        let result = QueryRangeItr::transform_truncated(query, content, |it| it.to_uppercase(), 14);
+        assert_eq!(result, "haystackNEEDLE");
+    }
+
+    #[test]
+    fn capitalizes_query_only_at_sentence_start() {
+        let content = "the cat sat. the end? yes! the\tlast. the";
+        let result = QueryRangeItr::capitalize_query_at_sentence_start("the", content);
+        assert_eq!(result, "The cat sat. The end? yes! The\tlast. The");
+    }
+
+    #[test]
+    fn does_not_capitalize_mid_sentence_query() {
+        let content = "  over there.the bathe, then the";
+        let result = QueryRangeItr::capitalize_query_at_sentence_start("the", content);
+        assert_eq!(result, "  over there.the bathe, then the");
+        let result = QueryRangeItr::capitalize_query_at_sentence_start("over", content);
+        assert_eq!(result, "  Over there.the bathe, then the");
+    }
+
+    #[test]
+    fn html_highlight_escapes_content() {
+        let content = "<script>alert(\"needle\")</script> & needle";
+        let result = QueryRangeItr::to_html_highlighted("needle", content, "match");
+        assert_eq!(
+            result,
+            "&lt;script&gt;alert(&quot;<span class=\"match\">needle</span>&quot;)&lt;/script&gt; &amp; \
+            <span class=\"match\">needle</span>",
+        );
+    }
+
+    #[test]
+    fn html_highlight_escapes_matches_and_class() {
+        let result = QueryRangeItr::to_html_highlighted("<i>", "a<i>b", "\"x");
+        assert_eq!(result, "a<span class=\"&quot;x\">&lt;i&gt;</span>b");
+        let result = QueryRangeItr::to_html_highlighted("needle", "haystack", "match");
+        assert_eq!(result, "haystack");
+    }
+
+    #[test]
+    fn can_wrap_matches() {
+        assert_eq!(QueryRangeItr::wrap_matches("needle", "aneedleb", "[", "]"), "a[needle]b");
+        assert_eq!(QueryRangeItr::wrap_matches("needle", "needleneedle", "[", "]"), "[needle][needle]");
+        assert_eq!(QueryRangeItr::wrap_matches("needle", "haystack", "[", "]"), "haystack");
+    }
+
+    #[test]
+    fn can_replace_all_matches() {
+        let content = "haystackneedlehaystackneedle";
+        assert_eq!(QueryRangeItr::replace("needle", content, "pin"), "haystackpinhaystackpin");
+        assert_eq!(QueryRangeItr::replace("pin", content, "needle"), content);
+    }
+
+    #[test]
+    fn replacement_containing_query_is_not_rescanned() {
+        assert_eq!(QueryRangeItr::replace("a", "aba", "aa"), "aabaa");
+        assert_eq!(QueryRangeItr::replace_n("a", "aba", "aa", 1), "aaba");
+    }
+
+    #[test]
+    fn can_replace_first_n_matches() {
+        let content = "x x x";
+        assert_eq!(QueryRangeItr::replace_n("x", content, "y", 0), content);
+        assert_eq!(QueryRangeItr::replace_n("x", content, "y", 1), "y x x");
+        assert_eq!(QueryRangeItr::replace_n("x", content, "y", 99), "y y y");
+    }
+
+    #[test]
+    fn can_replace_selected_indices() {
+        let content = "cat-cat-cat-cat";
+        assert_eq!(QueryRangeItr::replace_indices("cat", content, "dog", &[0, 2]), "dog-cat-dog-cat");
+        assert_eq!(QueryRangeItr::replace_indices("cat", content, "dog", &[3]), "cat-cat-cat-dog");
+        assert_eq!(QueryRangeItr::replace_indices("cat", content, "dog", &[]), content);
+    }
+
+    #[test]
+    fn replace_indices_ignores_out_of_range_indices() {
+        let content = "cat-cat";
+        assert_eq!(QueryRangeItr::replace_indices("cat", content, "dog", &[1, 5, 9]), "cat-dog");
+    }
+
+    #[test]
+    fn applying_diff_replace_edits_equals_full_replace() {
+        let query = "needle";
+        let content = "needlehaystackneedlehaystackneedleneedle";
+        for replacement in ["", "pin", "needleneedle"] {
+            let edits = QueryRangeItr::diff_replace(query, content, replacement);
+            assert_eq!(edits.len(), 4);
+            let mut buffer = String::from(content);
+            for edit in edits.iter() {
+                buffer.replace_range(edit.range.clone(), &edit.insert);
+            }
+            assert_eq!(buffer, content.replace(query, replacement));
+        }
+    }
+
+    #[test]
+    fn can_reconstruct_content_after_extract_and_strip() {
+        let content = "needlehaystackneedlehaystackneedle";
+        let (stripped, removed) = QueryRangeItr::extract_and_strip("needle", content);
+        assert_eq!(stripped, "haystackhaystack");
+        assert_eq!(removed.iter().map(|(range, _)| range.clone()).collect::<Vec<_>>(), vec![0..6, 14..20, 28..34]);
+        assert!(removed.iter().all(|(_, text)| text == "needle"));
+        let mut reconstructed = stripped.clone();
+        for (range, text) in removed.iter() {
+            reconstructed.insert_str(range.start, text);
+        }
+        assert_eq!(reconstructed, content);
+    }
+
+    #[test]
+    fn can_iterate_repeat_matcher_matches() {
+        let content = "ab abab abababab é ab";
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_with_matcher(content, repeat_matcher("ab", 2, 3)).collect();
+        assert_eq!(ranges, vec![3..7, 8..14]);
+    }
+
+    #[test]
+    fn matcher_lengths_are_snapped_and_empty_matches_skipped() {
+        let content = "aéaé";
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_with_matcher(content, |rest: &str| {
+            if rest.starts_with('a') { Some(2) } else { Some(0) }
+        }).collect();
+        assert_eq!(ranges, vec![0..3, 3..6]);
+        let huge: Vec<Range<usize>> = QueryRangeItr::new_with_matcher(content, |_: &str| Some(usize::MAX)).collect();
+        assert_eq!(huge, vec![0..6]);
+    }
+
+    #[test]
+    fn greedy_glob_takes_longest_match() {
+        let content = "aXbYb aab";
+        let lazy: Vec<Range<usize>> = QueryRangeItr::new_glob("a*b", content).collect();
+        let greedy: Vec<Range<usize>> = QueryRangeItr::new_glob_greedy("a*b", content).collect();
+        assert_eq!(lazy, vec![0..3, 6..9]);
+        assert_eq!(greedy, vec![0..9]);
+    }
+
+    #[test]
+    fn glob_question_mark_matches_one_char() {
+        let content = "cat cot cart cét";
+        let ranges: Vec<Range<usize>> = QueryRangeItr::new_glob("c?t", content).collect();
+        assert_eq!(ranges, vec![0..3, 4..7, 13..17]);
+        assert_eq!(QueryRangeItr::new_glob("", content).count(), 0);
+        assert_eq!(QueryRangeItr::new_glob("*", content).count(), content.chars().count());
+        assert_eq!(QueryRangeItr::new_glob_greedy("*", content).collect::<Vec<_>>(), vec![0..content.len()]);
+        assert_eq!(QueryRangeItr::new_glob("x*", content).count(), 0);
+    }
+
+    #[test]
+    fn diff_replace_is_empty_without_matches() {
+        assert!(QueryRangeItr::diff_replace("needle", "haystack", "pin").is_empty());
     }
 
     #[test]
@@ -0,0 +1,27 @@
+use std::error::Error;
+use std::fmt;
+
+/// An error found while validating a list of ranges, identifying the offending range by its index
+/// in the list. For sorting and overlap errors, the offending pair is that range and the one just
+/// before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeError {
+    /// The range at this index has a start greater than its end.
+    Inverted(usize),
+    /// The range at this index starts before the range prior to it.
+    Unsorted(usize),
+    /// The range at this index starts before the range prior to it ends.
+    Overlapping(usize),
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeError::Inverted(index) => write!(f, "range {} has a start greater than its end", index),
+            RangeError::Unsorted(index) => write!(f, "range {} starts before range {}", index, index - 1),
+            RangeError::Overlapping(index) => write!(f, "range {} overlaps range {}", index, index - 1),
+        }
+    }
+}
+
+impl Error for RangeError {}
@@ -0,0 +1,25 @@
+use std::ops::Range;
+
+/// A byte range of content that is either a match of the query or the content in between matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    /// A range matching the query.
+    Match(Range<usize>),
+    /// A range of content that doesn't match the query.
+    Other(Range<usize>),
+}
+
+impl Segment {
+
+    /// Gets the byte range of the segment, whether or not it's a match.
+    pub fn range(&self) -> Range<usize> {
+        match self {
+            Segment::Match(range) | Segment::Other(range) => range.clone(),
+        }
+    }
+
+    /// Checks if the segment is a match of the query.
+    pub fn is_match(&self) -> bool {
+        matches!(self, Segment::Match(_))
+    }
+}
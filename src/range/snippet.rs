@@ -0,0 +1,18 @@
+use std::ops::Range;
+
+/// A match packaged with the context surrounding it, such as for a search result card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    /// The context window around the match, including the match itself.
+    pub text: String,
+    /// The byte range of the match within `text`.
+    pub match_in_snippet: Range<usize>,
+}
+
+impl Snippet {
+
+    /// Gets the matched text within the snippet.
+    pub fn matched(&self) -> &str {
+        &self.text[self.match_in_snippet.clone()]
+    }
+}
@@ -0,0 +1,82 @@
+use std::ops::Range;
+use super::query_range_iterator::QueryRangeItr;
+
+/// Match statistics accumulated while iterating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MatchStats {
+    /// The number of ranges yielded so far.
+    pub count: usize,
+    /// The combined byte length of all ranges yielded so far.
+    pub total_len: usize,
+}
+
+/// Wraps a `QueryRangeItr`, yielding its ranges unchanged while tracking match statistics.
+///
+/// ### Example
+/// ```
+/// use query_range::QueryRangeItr;
+///
+/// let content = "haystackneedlehaystackneedlehaystack";
+/// let mut itr = QueryRangeItr::new("needle", content).with_stats();
+/// while let Some(_) = itr.next() {}
+/// assert_eq!(itr.stats().count, 2);
+/// assert_eq!(itr.stats().total_len, 12);
+/// ```
+#[derive(Debug, Clone)]
+pub struct StatsIter<'a> {
+    inner: QueryRangeItr<'a>,
+    stats: MatchStats,
+}
+
+impl<'a> StatsIter<'a> {
+
+    /// Creates a new statistics iterator wrapping the given iterator.
+    pub fn new(inner: QueryRangeItr<'a>) -> StatsIter<'a> {
+        Self { inner, stats: MatchStats::default() }
+    }
+
+    /// Gets the statistics for all ranges yielded so far.
+    pub fn stats(&self) -> MatchStats {
+        self.stats
+    }
+}
+
+// Iterator implementation ----------------------------------------------------------------------- /
+
+impl<'a> Iterator for StatsIter<'a> {
+    type Item = Range<usize>;
+
+    /// Gets next range from the wrapped iterator, recording it in the statistics.
+    fn next(&mut self) -> Option<Self::Item> {
+        let range = self.inner.next()?;
+        self.stats.count += 1;
+        self.stats.total_len += range.len();
+        Some(range)
+    }
+}
+
+// Tests ----------------------------------------------------------------------------------------- /
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_match_a_separate_count_and_length() {
+        let query = "needle";
+        let content = "haystackneedlehaystackneedlehaystackneedle";
+        let mut itr = QueryRangeItr::new(query, content).with_stats();
+        let ranges: Vec<Range<usize>> = itr.by_ref().collect();
+        let expected_len: usize = QueryRangeItr::new(query, content).map(|range| range.len()).sum();
+        assert_eq!(itr.stats().count, QueryRangeItr::new(query, content).count());
+        assert_eq!(itr.stats().total_len, expected_len);
+        assert_eq!(ranges.len(), 3);
+    }
+
+    #[test]
+    fn stats_are_empty_without_matches() {
+        let mut itr = QueryRangeItr::new("needle", "haystack").with_stats();
+        assert_eq!(itr.next(), None);
+        assert_eq!(itr.stats(), MatchStats::default());
+    }
+}
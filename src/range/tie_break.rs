@@ -0,0 +1,12 @@
+/// The policy for choosing between several queries that match at the same position, such as when
+/// one query is a prefix of another. Where the policy still leaves a tie (equal lengths), the
+/// query given first wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// The query given first wins, regardless of match length.
+    FirstQuery,
+    /// The longest match wins.
+    LongestMatch,
+    /// The shortest match wins.
+    ShortestMatch,
+}
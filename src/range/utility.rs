@@ -1,21 +1,123 @@
-use std::ops::Range;
+use std::ops::{Range, Add, Sub};
+use std::collections::{HashMap, VecDeque};
 use num::{PrimInt};
+use super::query_range_iterator::QueryRangeItr;
+use super::byte_query_range_iterator::ByteQueryRangeItr;
+use super::segment::Segment;
+use super::range_error::RangeError;
+#[cfg(feature = "regex")]
+use regex::{Captures, Regex};
 
 // Utilities ------------------------------------------------------------------------------------- /
 
 /// Converts a string to title case (first letter capitalized and all the rest lower-case).
+/// Only the very first character is capitalized; see `to_title_case_words` to capitalize each word.
 pub fn to_title_case(content: &str) -> String {
-    if content.len() > 1 {
-        format!("{}{}", &content[0..1].to_uppercase(), &content[1..].to_lowercase())
-    } else if content.len() == 1 {
-        content[0..1].to_uppercase()
-    } else {
-        String::new()
+    title_case_word(content)
+}
+
+/// Converts each whitespace-separated word to title case (first letter capitalized and all the
+/// rest of the word lower-case). Whitespace is kept as is.
+///
+/// ## Example:
+/// ```
+/// use query_range::to_title_case_words;
+///
+/// assert_eq!(to_title_case_words("fooBar baz"), "Foobar Baz");
+/// ```
+pub fn to_title_case_words(content: &str) -> String {
+    map_words(content, title_case_word)
+}
+
+/// Converts each whitespace-separated word to title case, like `to_title_case`, but leaves
+/// acronyms untouched. An acronym is a word of two or more characters with no lowercase letters
+/// (and at least one letter), such as `"NASA"`. Whitespace is kept as is.
+///
+/// ## Example:
+/// ```
+/// use query_range::to_title_case_preserve_acronyms;
+///
+/// assert_eq!(to_title_case_preserve_acronyms("NASA report"), "NASA Report");
+/// assert_eq!(to_title_case_preserve_acronyms("nasa data"), "Nasa Data");
+/// ```
+pub fn to_title_case_preserve_acronyms(content: &str) -> String {
+    map_words(content, |word| if is_acronym(word) { String::from(word) } else { title_case_word(word) })
+}
+
+/// Converts a string to snake case, such as `"fooBar baz"` to `"foo_bar_baz"`. Words are split on
+/// whitespace, underscores, hyphens and camel case humps (an acronym such as `"HTTP"` stays one
+/// word).
+///
+/// ## Example:
+/// ```
+/// use query_range::to_snake_case;
+///
+/// assert_eq!(to_snake_case("parseHTTPRequest"), "parse_http_request");
+/// ```
+pub fn to_snake_case(content: &str) -> String {
+    case_words(content).join("_")
+}
+
+/// Converts a string to kebab case, such as `"fooBar baz"` to `"foo-bar-baz"`. Words are split as
+/// with `to_snake_case`.
+///
+/// ## Example:
+/// ```
+/// use query_range::to_kebab_case;
+///
+/// assert_eq!(to_kebab_case("parseHTTPRequest"), "parse-http-request");
+/// ```
+pub fn to_kebab_case(content: &str) -> String {
+    case_words(content).join("-")
+}
+
+/// Converts a string to camel case, such as `"foo_bar baz"` to `"fooBarBaz"`. Words are split as
+/// with `to_snake_case`.
+///
+/// ## Example:
+/// ```
+/// use query_range::to_camel_case;
+///
+/// assert_eq!(to_camel_case("parse-HTTP-request"), "parseHttpRequest");
+/// ```
+pub fn to_camel_case(content: &str) -> String {
+    case_words(content)
+        .iter()
+        .enumerate()
+        .map(|(index, word)| if index == 0 { word.clone() } else { title_case_word(word) })
+        .collect()
+}
+
+/// Swaps the case of each character (uppercase to lowercase and lowercase to uppercase).
+/// Characters without case, such as digits and punctuation, are left unchanged.
+///
+/// ## Example:
+/// ```
+/// use query_range::toggle_case;
+///
+/// assert_eq!(toggle_case("Hello"), "hELLO");
+/// ```
+pub fn toggle_case(content: &str) -> String {
+    let mut toggled = String::with_capacity(content.len());
+    for character in content.chars() {
+        if character.is_uppercase() {
+            toggled.extend(character.to_lowercase());
+        } else if character.is_lowercase() {
+            toggled.extend(character.to_uppercase());
+        } else {
+            toggled.push(character);
+        }
     }
+    toggled
 }
 
 /// Gets first range of given query in given content.
+///
+/// An empty query is treated as "no match" and always returns `None`.
 pub fn get_range(query: &str, content: &str) -> Option<Range<usize>> {
+    if query.is_empty() {
+        return None;
+    }
     let possible_start = content.find(query);
     if let Some(start) = possible_start {
         let end: usize = start + query.len();
@@ -29,6 +131,272 @@ pub fn get_range(query: &str, content: &str) -> Option<Range<usize>> {
     }
 }
 
+/// Gets last range of given query in given content, such as the last path separator. Where
+/// candidates overlap, the one starting last is found.
+///
+/// An empty query is treated as "no match" and always returns `None`.
+///
+/// ## Example:
+/// ```
+/// use query_range::get_last_range;
+///
+/// assert_eq!(get_last_range("/", "a/b/c"), Some(3..4));
+/// assert_eq!(get_last_range("aa", "aaa"), Some(1..3));
+/// ```
+pub fn get_last_range(query: &str, content: &str) -> Option<Range<usize>> {
+    if query.is_empty() {
+        return None;
+    }
+    content.rfind(query).map(|start| start..(start + query.len()))
+}
+
+/// Gets all non-overlapping ranges of given query in given content, the same ranges as collecting
+/// `QueryRangeItr::new`. An empty query, or one without a match, gives an empty vector.
+///
+/// ## Example:
+/// ```
+/// use query_range::get_all_ranges;
+///
+/// assert_eq!(get_all_ranges("ab", "abxab"), vec![0..2, 3..5]);
+/// ```
+pub fn get_all_ranges(query: &str, content: &str) -> Vec<Range<usize>> {
+    QueryRangeItr::new(query, content).collect()
+}
+
+/// Gets first range of given byte query in given byte content, without requiring either to be valid
+/// UTF-8. Candidates are found by scanning for the query's first byte, then the rest is compared.
+/// An empty query is treated as "no match" and always returns `None`.
+///
+/// ## Example:
+/// ```
+/// use query_range::get_byte_range;
+///
+/// assert_eq!(get_byte_range(b"\x00\x01", b"ab\x00\x01cd"), Some(2..4));
+/// ```
+pub fn get_byte_range(query: &[u8], content: &[u8]) -> Option<Range<usize>> {
+    let (first, rest) = query.split_first()?;
+    let mut offset: usize = 0;
+    while content.len() - offset >= query.len() {
+        let candidate = offset + content[offset..].iter().position(|byte| byte == first)?;
+        let end = candidate + query.len();
+        if end > content.len() {
+            return None;
+        }
+        if &content[(candidate + 1)..end] == rest {
+            return Some(candidate..end);
+        }
+        offset = candidate + 1;
+    }
+    None
+}
+
+/// Iterates all non-overlapping ranges of a byte query in byte content, such as a memory-mapped
+/// file, without requiring valid UTF-8. This is a shorthand for `ByteQueryRangeItr::new`.
+///
+/// ## Example:
+/// ```
+/// use query_range::search_bytes;
+///
+/// let ranges: Vec<_> = search_bytes(b"ab", b"xabxxab").collect();
+/// assert_eq!(ranges, vec![1..3, 5..7]);
+/// ```
+pub fn search_bytes<'a>(
+    query: &'a [u8],
+    haystack: &'a [u8],
+) -> impl Iterator<Item = Range<usize>> + 'a {
+    ByteQueryRangeItr::new(query, haystack)
+}
+
+/// Searches content that is already split into lines as if the lines were one document joined by
+/// `\n`, without allocating the joined string. Each match is yielded as the index of the line it
+/// was found in and its range within that line. Matches never span lines.
+///
+/// ## Example:
+/// ```
+/// use query_range::search_lines;
+///
+/// let lines = vec!["a needle", "no match", "needle and needle"];
+/// let matches: Vec<_> = search_lines("needle", &lines).collect();
+/// assert_eq!(matches, vec![(0, 2..8), (2, 0..6), (2, 11..17)]);
+/// ```
+pub fn search_lines<'a>(query: &'a str, lines: &'a [&'a str]) -> impl Iterator<Item = (usize, Range<usize>)> + 'a {
+    lines.iter().enumerate().flat_map(move |(index, line)| {
+        QueryRangeItr::new(query, line).map(move |range| (index, range))
+    })
+}
+
+/// Searches a stream of characters (where the full content isn't available as a `&str`) for the
+/// query, yielding the *char index* range of each match as soon as it completes. Only a rolling
+/// buffer the length of the query is kept. Matches don't overlap.
+///
+/// ## Example:
+/// ```
+/// use query_range::search_char_stream;
+///
+/// let ranges: Vec<_> = search_char_stream("né", "anénéb".chars()).collect();
+/// assert_eq!(ranges, vec![1..3, 3..5]);
+/// ```
+pub fn search_char_stream<I>(query: &str, chars: I) -> impl Iterator<Item = Range<usize>>
+    where I: Iterator<Item = char>
+{
+    let query: Vec<char> = query.chars().collect();
+    let mut buffer: VecDeque<char> = VecDeque::with_capacity(query.len());
+    let mut chars = chars.enumerate();
+    std::iter::from_fn(move || {
+        if query.is_empty() {
+            return None;
+        }
+        for (index, character) in chars.by_ref() {
+            if buffer.len() == query.len() {
+                buffer.pop_front();
+            }
+            buffer.push_back(character);
+            if buffer.len() == query.len() && buffer.iter().eq(query.iter()) {
+                buffer.clear();
+                return Some((index + 1 - query.len())..(index + 1));
+            }
+        }
+        None
+    })
+}
+
+/// Gets the match of the query whose start is closest to the given byte offset, such as when
+/// jumping to the nearest occurrence from a cursor. When a match before and a match after the
+/// offset are equally close, the one at or after the offset wins.
+///
+/// ## Example:
+/// ```
+/// use query_range::nearest_match;
+///
+/// let content = "needle....needle";
+/// assert_eq!(nearest_match("needle", content, 3), Some(0..6));
+/// assert_eq!(nearest_match("needle", content, 8), Some(10..16));
+/// ```
+pub fn nearest_match(query: &str, content: &str, offset: usize) -> Option<Range<usize>> {
+    QueryRangeItr::new(query, content)
+        .min_by_key(|range| (range.start.abs_diff(offset), range.start < offset))
+}
+
+/// Gets the match of the query with the most room for context on both sides: the one whose
+/// distance to the nearest edge of the content is greatest. Ties go to the earlier match.
+///
+/// ## Example:
+/// ```
+/// use query_range::most_centered_match;
+///
+/// let content = "needle.....needle.....needle";
+/// assert_eq!(most_centered_match("needle", content), Some(11..17));
+/// ```
+pub fn most_centered_match(query: &str, content: &str) -> Option<Range<usize>> {
+    QueryRangeItr::new(query, content)
+        .max_by_key(|range| (range.start.min(content.len() - range.end), std::cmp::Reverse(range.start)))
+}
+
+/// Gets the smallest range of the content that contains at least one occurrence of every query,
+/// such as for building a snippet around all keywords. Ties go to the earliest window. Returns
+/// `None` if there are no queries or any query isn't found.
+///
+/// ## Example:
+/// ```
+/// use query_range::min_window;
+///
+/// let content = "cat ... dog ... bird cat dog";
+/// assert_eq!(min_window(&["dog", "cat", "bird"], content), Some(16..28));
+/// ```
+pub fn min_window(queries: &[&str], content: &str) -> Option<Range<usize>> {
+    if queries.is_empty() {
+        return None;
+    }
+    let mut occurrences: Vec<Vec<usize>> = Vec::with_capacity(queries.len());
+    for query in queries {
+        let starts = all_starts(query, content);
+        if starts.is_empty() {
+            return None;
+        }
+        occurrences.push(starts);
+    }
+    let mut best: Option<Range<usize>> = None;
+    for left in occurrences.iter().flatten() {
+        let end = queries.iter().zip(occurrences.iter()).try_fold(*left, |end, (query, starts)| {
+            let index = starts.partition_point(|start| start < left);
+            starts.get(index).map(|start| end.max(start + query.len()))
+        });
+        if let Some(end) = end {
+            let is_better = match &best {
+                Some(best) => (end - left, *left) < (best.len(), best.start),
+                None => true,
+            };
+            if is_better {
+                best = Some(*left..end);
+            }
+        }
+    }
+    best
+}
+
+/// Gets the content following the last match of the query, such as a file extension after the last
+/// dot. Returns the whole content if the query isn't found.
+///
+/// ## Example:
+/// ```
+/// use query_range::after_last;
+///
+/// assert_eq!(after_last(".", "a.b.c"), "c");
+/// assert_eq!(after_last(".", "abc"), "abc");
+/// ```
+pub fn after_last<'a>(query: &str, content: &'a str) -> &'a str {
+    match get_last_range(query, content) {
+        Some(range) => &content[range.end..],
+        None => content,
+    }
+}
+
+/// Gets the content preceding the first match of the query. Returns the whole content if the query
+/// isn't found.
+///
+/// ## Example:
+/// ```
+/// use query_range::before_first;
+///
+/// assert_eq!(before_first(".", "a.b.c"), "a");
+/// assert_eq!(before_first(".", "abc"), "abc");
+/// ```
+pub fn before_first<'a>(query: &str, content: &'a str) -> &'a str {
+    match get_range(query, content) {
+        Some(range) => &content[..range.start],
+        None => content,
+    }
+}
+
+/// Computes a relevance score for the content against the query, for ranking several documents by
+/// a single query. The score is in `0.0..=1.0`, where higher is more relevant.
+///
+/// The formula is `coverage + coverage * (1.0 - coverage) * count / (count + 1)`, where `count` is
+/// the number of non-overlapping matches and `coverage` is the matched byte length divided by the
+/// content byte length. Density dominates: the count term at most doubles a low coverage, so a
+/// long document with many sparse matches still scores low, and it shrinks to nothing as coverage
+/// reaches `1.0`. Content that is entirely matches scores `1.0`, and content with no matches (or
+/// empty content) scores `0.0`.
+///
+/// ## Example:
+/// ```
+/// use query_range::relevance;
+///
+/// let more = relevance("needle", "needlehaystackneedle");
+/// let fewer = relevance("needle", "needlehaystackhaystack");
+/// assert!(more > fewer);
+/// ```
+pub fn relevance(query: &str, content: &str) -> f64 {
+    if content.is_empty() {
+        return 0.0;
+    }
+    let (count, matched_len) = QueryRangeItr::new(query, content)
+        .fold((0usize, 0usize), |(count, len), range| (count + 1, len + range.len()));
+    let coverage = matched_len as f64 / content.len() as f64;
+    let count = count as f64;
+    coverage + coverage * (1.0 - coverage) * count / (count + 1.0)
+}
+
 /// Enum to specify the direction of a shift, up or down with amount (magnitude).
 pub enum Shift<T> where T: PrimInt {
     /// Shifts a number/range *up* by specified amount.
@@ -62,6 +430,74 @@ impl<T> Shift<T> where T: PrimInt {
             None
         }
     }
+
+    /// Applies the shift to a range, clamping the start and end values at the numeric bounds of the
+    /// type instead of overflowing.
+    fn apply_to_range_saturating(&self, range: Range<T>) -> Range<T> {
+        match self {
+            Shift::Up(amount) => range.start.saturating_add(*amount)..range.end.saturating_add(*amount),
+            Shift::Down(amount) => range.start.saturating_sub(*amount)..range.end.saturating_sub(*amount),
+        }
+    }
+
+    /// Shifts a range by this shift, producing a new range with the start and end values shifted
+    /// by the amount. Returns `None` if any overflow occurs.
+    ///
+    /// ## Example:
+    /// ```
+    /// use query_range::Shift;
+    ///
+    /// assert_eq!(Shift::Up(2).shift(0..5), Some(2..7));
+    /// assert_eq!(Shift::Down(2usize).shift(0..5), None);
+    /// ```
+    pub fn shift(&self, range: Range<T>) -> Option<Range<T>> {
+        self.apply_to_range(range)
+    }
+
+    /// Gets the shift of the same amount in the opposite direction.
+    fn inverse(self) -> Shift<T> {
+        match self {
+            Shift::Up(amount) => Shift::Down(amount),
+            Shift::Down(amount) => Shift::Up(amount),
+        }
+    }
+}
+
+/// Shifts a range with `+`, so `range + Shift::Up(2)` reads like arithmetic. As the shift may
+/// overflow, the result is an `Option`.
+///
+/// ## Example:
+/// ```
+/// use query_range::Shift;
+///
+/// assert_eq!((0..5) + Shift::Up(2), Some(2..7));
+/// assert_eq!((4..7) + Shift::Down(3), Some(1..4));
+/// ```
+impl<T> Add<Shift<T>> for Range<T> where T: PrimInt {
+    type Output = Option<Range<T>>;
+
+    fn add(self, shift: Shift<T>) -> Self::Output {
+        shift.apply_to_range(self)
+    }
+}
+
+/// Shifts a range with `-`, applying the shift in the opposite direction, so
+/// `range - Shift::Up(2)` moves the range down by 2. As the shift may overflow, the result is an
+/// `Option`.
+///
+/// ## Example:
+/// ```
+/// use query_range::Shift;
+///
+/// assert_eq!((2..7) - Shift::Up(2), Some(0..5));
+/// assert_eq!((0usize..5) - Shift::Up(2), None);
+/// ```
+impl<T> Sub<Shift<T>> for Range<T> where T: PrimInt {
+    type Output = Option<Range<T>>;
+
+    fn sub(self, shift: Shift<T>) -> Self::Output {
+        shift.inverse().apply_to_range(self)
+    }
 }
 
 /// Creates a new range with the start and end values shifted by the given amount.
@@ -82,6 +518,44 @@ pub fn shift_range<T>(range: Range<T>, shift: Shift<T>) -> Option<Range<T>> wher
     shift.apply_to_range(range)
 }
 
+/// Creates a new range with the start and end values shifted independently, such as moving the
+/// start past trimmed whitespace while keeping the end fixed. Returns `None` if any overflow occurs
+/// or the resulting range would be inverted (start after end).
+///
+/// ## Example:
+/// ```
+/// use query_range::{shift_range_bounds, Shift};
+///
+/// assert_eq!(shift_range_bounds(2..10, Shift::Up(3), Shift::Up(0)), Some(5..10));
+/// assert_eq!(shift_range_bounds(2..10, Shift::Up(5), Shift::Down(5)), None);
+/// ```
+pub fn shift_range_bounds<T>(range: Range<T>, start_shift: Shift<T>, end_shift: Shift<T>) -> Option<Range<T>>
+    where T: PrimInt
+{
+    let start = start_shift.apply_to_number(range.start)?;
+    let end = end_shift.apply_to_number(range.end)?;
+    if start <= end {
+        Some(start..end)
+    } else {
+        None
+    }
+}
+
+/// Creates a new range with the start and end values shifted by the given amount, clamping each
+/// value at the numeric bounds of the type (zero for unsigned types) rather than returning `None`
+/// on overflow, such as when normalizing ranges after trimming content.
+///
+/// ## Example:
+/// ```
+/// use query_range::{shift_range_saturating, Shift};
+///
+/// assert_eq!(shift_range_saturating(0usize..5, Shift::Down(10)), 0..0);
+/// assert_eq!(shift_range_saturating(3usize..5, Shift::Down(4)), 0..1);
+/// ```
+pub fn shift_range_saturating<T>(range: Range<T>, shift: Shift<T>) -> Range<T> where T: PrimInt {
+    shift.apply_to_range_saturating(range)
+}
+
 /// Creates a new range with the start and end values shifted by the given amount *and* checks that
 /// the range is valid in the given content. If new range falls outside of the given content, `None`
 /// will be returned.
@@ -99,20 +573,36 @@ pub fn shift_range<T>(range: Range<T>, shift: Shift<T>) -> Option<Range<T>> wher
 /// assert_eq!(shift_range_in_content(range, Shift::Up(20), test_str), None);
 /// ```
 pub fn shift_range_in_content<T>(range: Range<T>, shift: Shift<T>, content: &str) -> Option<Range<T>> where T: PrimInt {
-    let new_range = shift_range(range, shift);
-    if let Some(new_range) = new_range {
-        if is_within(content, &new_range) {
-            Some(new_range)
-        } else {
-            None
+    shift_range(range, shift).filter(|new_range| is_within(content, new_range))
+}
+
+/// Shifts every range whose start is at or after the given offset, leaving earlier ranges
+/// untouched, such as for keeping a set of matches up to date after text is inserted or removed at
+/// `at`. The shift is checked, so a range that would overflow is also left untouched.
+///
+/// ## Example:
+/// ```
+/// use query_range::{shift_ranges_after, Shift};
+///
+/// let mut ranges = vec![0..2, 5..8, 10..12];
+/// shift_ranges_after(&mut ranges, 5, Shift::Up(3));
+/// assert_eq!(ranges, vec![0..2, 8..11, 13..15]);
+/// ```
+pub fn shift_ranges_after(ranges: &mut [Range<usize>], at: usize, by: Shift<usize>) {
+    for range in ranges.iter_mut().filter(|range| range.start >= at) {
+        if let Some(shifted) = by.apply_to_range(range.clone()) {
+            *range = shifted;
         }
-    } else {
-        None
     }
 }
 
 /// Checks if a closed range exists in given string content.
 ///
+/// A zero-width range (such as `0..0`) is within any content whose length is at least its end,
+/// including empty content, since it selects nothing. Use `is_within_strict` to also require a
+/// non-empty range. An inverted range (start after end), or one starting or ending past the end of
+/// the content (or before zero), is never within.
+///
 /// ## Example:
 /// ```
 /// use query_range::is_within;
@@ -126,43 +616,1276 @@ pub fn shift_range_in_content<T>(range: Range<T>, shift: Shift<T>, content: &str
 /// assert_eq!(is_within(test_str, &range02), false);
 /// ```
 pub fn is_within<T>(content: &str, range: &Range<T>) -> bool where T: PrimInt {
-    let range_end = range.end.to_usize();
-    if let Some(range_end) = range_end {
-        if range_end > content.len() {
-            false
-        } else {
-            true
-        }
-    } else {
-        false
+    match (range.start.to_usize(), range.end.to_usize()) {
+        (Some(start), Some(end)) => start <= end && end <= content.len(),
+        _ => false,
     }
 }
 
-// Tests ----------------------------------------------------------------------------------------- /
+/// Checks if a range exists in given string content, like `is_within`, and is also non-empty
+/// (`start < end`), so zero-width ranges are rejected.
+///
+/// ## Example:
+/// ```
+/// use query_range::{is_within, is_within_strict};
+///
+/// let test_str = "this is a test";
+///
+/// assert!(is_within_strict(test_str, &(0..2)));
+/// assert!(is_within(test_str, &(0..0)));
+/// assert!(!is_within_strict(test_str, &(0..0)));
+/// ```
+pub fn is_within_strict<T>(content: &str, range: &Range<T>) -> bool where T: PrimInt {
+    range.start < range.end && is_within(content, range)
+}
 
-#[cfg(test)]
-mod test {
-    use super::*;
+/// Checks if a range (perhaps computed elsewhere) is actually a match of the query in the content:
+/// the range must be within the content and the content at that range must equal the query. An
+/// empty query never matches.
+///
+/// ## Example:
+/// ```
+/// use query_range::is_match_at;
+///
+/// let content = "haystackneedlehaystack";
+/// assert!(is_match_at("needle", content, &(8..14)));
+/// assert!(!is_match_at("needle", content, &(9..15)));
+/// ```
+pub fn is_match_at(query: &str, content: &str, range: &Range<usize>) -> bool {
+    !query.is_empty() && is_within(content, range) && content.get(range.clone()) == Some(query)
+}
 
-    #[test]
-    fn can_convert_to_title_case() {
-        assert_eq!(to_title_case("fooBarBaz"), "Foobarbaz");
-        assert_eq!(to_title_case("f"), "F");
-        assert_eq!(to_title_case(""), "");
+/// Tightens a range to exclude ASCII whitespace at either end of the slice it covers, such as for
+/// matches that can include spaces. A range covering only whitespace collapses to `start..start`.
+/// As ASCII whitespace is a single byte, the result stays on char boundaries.
+///
+/// Panics if the range isn't valid in the content, as slicing the content would.
+///
+/// ## Example:
+/// ```
+/// use query_range::trim_range;
+///
+/// let content = "a  needle  b";
+/// assert_eq!(trim_range(content, 1..11), 3..9);
+/// ```
+pub fn trim_range(content: &str, range: Range<usize>) -> Range<usize> {
+    let slice = &content[range.clone()];
+    let leading = slice.len() - slice.trim_start_matches(|c: char| c.is_ascii_whitespace()).len();
+    if leading == slice.len() {
+        return range.start..range.start;
     }
+    let trailing = slice.len() - slice.trim_end_matches(|c: char| c.is_ascii_whitespace()).len();
+    (range.start + leading)..(range.end - trailing)
+}
 
-    #[test]
-    fn can_apply_a_shift_to_a_number() {
-        let number = 5;
-        let shift = Shift::Up(2);
-        assert_eq!(shift.apply_to_number(number), Some(7));
-        let number = 8;
-        let shift = Shift::Down(3);
-        assert_eq!(shift.apply_to_number(number), Some(5));
+/// Moves a byte offset down to the nearest char boundary at or below it, such as when clamping or
+/// truncating at an arbitrary byte offset. Offsets past the end are clamped to the content length.
+///
+/// ## Example:
+/// ```
+/// use query_range::snap_boundary_down;
+///
+/// assert_eq!(snap_boundary_down("aéb", 2), 1);
+/// assert_eq!(snap_boundary_down("aéb", 3), 3);
+/// ```
+pub fn snap_boundary_down(content: &str, offset: usize) -> usize {
+    let mut offset = offset.min(content.len());
+    while !content.is_char_boundary(offset) {
+        offset -= 1;
     }
+    offset
+}
 
-    #[test]
-    fn can_apply_a_shift_to_a_range() {
+/// Moves a byte offset up to the nearest char boundary at or above it, such as when expanding a
+/// range to whole chars. Offsets past the end are clamped to the content length.
+///
+/// ## Example:
+/// ```
+/// use query_range::snap_boundary_up;
+///
+/// assert_eq!(snap_boundary_up("aéb", 2), 3);
+/// assert_eq!(snap_boundary_up("aéb", 9), 4);
+/// ```
+pub fn snap_boundary_up(content: &str, offset: usize) -> usize {
+    let mut offset = offset.min(content.len());
+    while !content.is_char_boundary(offset) {
+        offset += 1;
+    }
+    offset
+}
+
+/// Checks that hand-built ranges are each ordered (`start <= end`), sorted by start and
+/// non-overlapping, as expected by functions such as `apply_replacements`. Touching ranges (one
+/// ending where the next starts) don't overlap. The first offending range is identified in the
+/// error.
+///
+/// ## Example:
+/// ```
+/// use query_range::{validate_ranges, RangeError};
+///
+/// assert_eq!(validate_ranges(&[0..2, 2..4, 6..6]), Ok(()));
+/// assert_eq!(validate_ranges(&[0..3, 2..4]), Err(RangeError::Overlapping(1)));
+/// ```
+pub fn validate_ranges(ranges: &[Range<usize>]) -> Result<(), RangeError> {
+    for (index, range) in ranges.iter().enumerate() {
+        if range.start > range.end {
+            return Err(RangeError::Inverted(index));
+        }
+        if let Some(previous) = index.checked_sub(1).map(|previous| &ranges[previous]) {
+            if range.start < previous.start {
+                return Err(RangeError::Unsorted(index));
+            }
+            if range.start < previous.end {
+                return Err(RangeError::Overlapping(index));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Sorts the ranges by start and merges any that overlap or touch (such as `0..3` and `3..5`) into
+/// a single range, giving a sorted, disjoint set of ranges.
+///
+/// ## Example:
+/// ```
+/// use query_range::merge_ranges;
+///
+/// assert_eq!(merge_ranges(vec![4..6, 0..3, 2..4, 8..9]), vec![0..6, 8..9]);
+/// ```
+pub fn merge_ranges<T>(mut ranges: Vec<Range<T>>) -> Vec<Range<T>> where T: PrimInt {
+    ranges.sort_unstable_by_key(|range| range.start);
+    let mut merged: Vec<Range<T>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Stitches a list of replacements into the content, replacing `content[range]` with each string.
+/// The replacements may be given in any order; they are sorted by start before splicing (keeping
+/// the given order for equal starts).
+///
+/// Overlap policy: replacements are applied left to right and any replacement that overlaps one
+/// already applied is skipped, so the earliest-starting replacement wins. Ranges that are not valid
+/// in the content (out of bounds, inverted or not on a char boundary) are skipped as well.
+///
+/// ## Example:
+/// ```
+/// use query_range::apply_replacements;
+///
+/// let replacements = vec![(6..11, String::from("there")), (0..5, String::from("Hi"))];
+/// assert_eq!(apply_replacements("hello world", replacements), "Hi there");
+/// ```
+pub fn apply_replacements<I>(content: &str, replacements: I) -> String
+    where I: IntoIterator<Item = (Range<usize>, String)>
+{
+    let mut replacements: Vec<(Range<usize>, String)> = replacements.into_iter().collect();
+    replacements.sort_by_key(|(range, _)| range.start);
+    let mut result = String::with_capacity(content.len());
+    let mut last_end: usize = 0;
+    for (range, replacement) in replacements {
+        if range.start < last_end || content.get(range.clone()).is_none() {
+            continue;
+        }
+        result.push_str(&content[last_end..range.start]);
+        result.push_str(&replacement);
+        last_end = range.end;
+    }
+    result.push_str(&content[last_end..]);
+    result
+}
+
+/// Concatenates the content at each segment's range, in the given order, such as after filtering
+/// or reordering the segments from `QueryRangeItr::segments`. Segments with ranges that are not
+/// valid in the content are skipped.
+///
+/// ## Example:
+/// ```
+/// use query_range::{QueryRangeItr, Segment, reassemble};
+///
+/// let content = "a,b,c";
+/// let others: Vec<Segment> = QueryRangeItr::segments(",", content)
+///     .into_iter()
+///     .filter(|segment| !segment.is_match())
+///     .collect();
+/// assert_eq!(reassemble(content, &others), "abc");
+/// ```
+pub fn reassemble(content: &str, segments: &[Segment]) -> String {
+    segments.iter().filter_map(|segment| content.get(segment.range())).collect()
+}
+
+/// Replaces every occurrence of each key in the map with its value in a single left-to-right pass,
+/// such as for templating with many substitutions. Where several keys match at the same position,
+/// the longest key wins. Inserted values are never re-scanned, so a value containing another key
+/// is left as is. Empty keys are ignored.
+///
+/// ## Example:
+/// ```
+/// use std::collections::HashMap;
+/// use query_range::replace_many;
+///
+/// let mut map = HashMap::new();
+/// map.insert("{name}", "Ann");
+/// map.insert("{place}", "{name}'s house");
+/// assert_eq!(replace_many("{name} at {place}", &map), "Ann at {name}'s house");
+/// ```
+pub fn replace_many(content: &str, map: &HashMap<&str, &str>) -> String {
+    let mut keys: Vec<&str> = map.keys().copied().filter(|key| !key.is_empty()).collect();
+    keys.sort_unstable_by_key(|key| std::cmp::Reverse(key.len()));
+    let mut result = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(character) = rest.chars().next() {
+        match keys.iter().find(|key| rest.starts_with(*key)) {
+            Some(key) => {
+                result.push_str(map[key]);
+                rest = &rest[key.len()..];
+            }
+            None => {
+                result.push(character);
+                rest = &rest[character.len_utf8()..];
+            }
+        }
+    }
+    result
+}
+
+/// Replaces every match of the regex pattern with the template, where `$1`-style references in the
+/// template are substituted with the text of that capture group (`$0` being the whole match). A
+/// `$$` inserts a literal `$`, and a `$` not followed by a digit is kept as is. References to a
+/// group that doesn't exist, or didn't take part in the match, insert nothing.
+///
+/// Requires the `regex` feature.
+///
+/// ## Example:
+/// ```
+/// use regex::Regex;
+/// use query_range::replace_all_regex;
+///
+/// let pattern = Regex::new(r"(\w+)@(\w+)").unwrap();
+/// assert_eq!(replace_all_regex(&pattern, "ann@home", "$2 has $1 ($$5)"), "home has ann ($5)");
+/// ```
+#[cfg(feature = "regex")]
+pub fn replace_all_regex(pattern: &Regex, content: &str, template: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut last_end: usize = 0;
+    for captures in pattern.captures_iter(content) {
+        if let Some(whole) = captures.get(0) {
+            result.push_str(&content[last_end..whole.start()]);
+            expand_template(template, &captures, &mut result);
+            last_end = whole.end();
+        }
+    }
+    result.push_str(&content[last_end..]);
+    result
+}
+
+/// Creates a matcher that matches between `min` and `max` (inclusive) consecutive copies of `unit`
+/// at the start of the content it's given, returning the total matched byte length. As many
+/// copies as possible (up to `max`) are matched. The matcher never reports an empty match, so an
+/// empty `unit` or zero copies is `None`. Pass it to `QueryRangeItr::new_with_matcher` to find
+/// every such run in the content.
+///
+/// ## Example:
+/// ```
+/// use query_range::repeat_matcher;
+///
+/// let matcher = repeat_matcher("ab", 2, 3);
+/// assert_eq!(matcher("ababx"), Some(4));
+/// assert_eq!(matcher("abababab"), Some(6));
+/// assert_eq!(matcher("abx"), None);
+/// ```
+pub fn repeat_matcher<'a>(unit: &'a str, min: usize, max: usize) -> impl Fn(&str) -> Option<usize> + 'a {
+    move |content: &str| {
+        if unit.is_empty() {
+            return None;
+        }
+        let mut count: usize = 0;
+        let mut rest = content;
+        while count < max && rest.starts_with(unit) {
+            count += 1;
+            rest = &rest[unit.len()..];
+        }
+        if count >= min && count > 0 {
+            Some(count * unit.len())
+        } else {
+            None
+        }
+    }
+}
+
+/// Counts the character edits needed to make the content a perfect tiling of `unit` (see
+/// `is_tiling`), so `0` means the content already is one.
+///
+/// This is a straightforward per-tile comparison rather than a full edit distance: the content is
+/// cut into unit-length tiles and each character that differs from the unit costs one
+/// substitution. A trailing partial tile costs the cheaper of deleting it or completing it (its
+/// substitutions plus the missing characters). Empty content needs one whole unit inserted. With an
+/// empty unit no tiling exists, and the cost of deleting all of the content is returned.
+///
+/// ## Example:
+/// ```
+/// use query_range::tiling_distance;
+///
+/// assert_eq!(tiling_distance("ab", "abab"), 0);
+/// assert_eq!(tiling_distance("ab", "abxb"), 1);
+/// ```
+pub fn tiling_distance(unit: &str, content: &str) -> usize {
+    let unit: Vec<char> = unit.chars().collect();
+    let content: Vec<char> = content.chars().collect();
+    if unit.is_empty() {
+        return content.len();
+    }
+    if content.is_empty() {
+        return unit.len();
+    }
+    let mismatches = |tile: &[char]| tile.iter().zip(unit.iter()).filter(|(a, b)| a != b).count();
+    let mut tiles = content.chunks_exact(unit.len());
+    let full_cost: usize = tiles.by_ref().map(mismatches).sum();
+    let tail = tiles.remainder();
+    let tail_cost = if tail.is_empty() {
+        0
+    } else {
+        let complete = mismatches(tail) + unit.len() - tail.len();
+        if content.len() > tail.len() { complete.min(tail.len()) } else { complete }
+    };
+    full_cost + tail_cost
+}
+
+/// Iterates the byte range of each occurrence of any of the given characters in the content, such
+/// as for splitting on several delimiters.
+///
+/// ## Example:
+/// ```
+/// use query_range::find_char_ranges;
+///
+/// let ranges: Vec<_> = find_char_ranges("a,b;c", &[',', ';']).collect();
+/// assert_eq!(ranges, vec![1..2, 3..4]);
+/// ```
+pub fn find_char_ranges<'a>(content: &'a str, chars: &'a [char]) -> impl Iterator<Item = Range<usize>> + 'a {
+    content
+        .char_indices()
+        .filter(move |(_, character)| chars.contains(character))
+        .map(|(index, character)| index..(index + character.len_utf8()))
+}
+
+/// Checks if the content is exactly `unit` repeated one or more times with no remainder, such as
+/// `"abab"` for `"ab"`. An empty unit (or empty content) is never a tiling.
+///
+/// ## Example:
+/// ```
+/// use query_range::is_tiling;
+///
+/// assert!(is_tiling("ab", "abab"));
+/// assert!(!is_tiling("ab", "aba"));
+/// ```
+pub fn is_tiling(unit: &str, content: &str) -> bool {
+    if unit.is_empty() || content.is_empty() {
+        return false;
+    }
+    content.as_bytes().chunks(unit.len()).all(|chunk| chunk == unit.as_bytes())
+}
+
+// Crate helpers --------------------------------------------------------------------------------- /
+
+/// Checks if a character is part of a word (alphanumeric, including accented letters, or `_`).
+pub(crate) fn is_word_char(character: char) -> bool {
+    character.is_alphanumeric() || character == '_'
+}
+
+/// Checks if the character before the given byte index is not a word character (or the index is
+/// at the start of the content).
+pub(crate) fn is_word_boundary_before(content: &str, index: usize) -> bool {
+    !content[..index].chars().next_back().is_some_and(is_word_char)
+}
+
+/// Checks if the character at the given byte index is not a word character (or the index is at the
+/// end of the content).
+pub(crate) fn is_word_boundary_after(content: &str, index: usize) -> bool {
+    !content[index..].chars().next().is_some_and(is_word_char)
+}
+
+/// Checks if the query can overlap itself, as in `"aa"` or `"abab"`, meaning that a proper prefix
+/// of it is also a suffix of it.
+pub(crate) fn overlaps_itself(query: &str) -> bool {
+    (1..query.len()).any(|len| query.is_char_boundary(len) && query.ends_with(&query[..len]))
+}
+
+/// Gets the byte length of the match of the glob pattern at the start of the content, where `*`
+/// matches any run of characters (including none) and `?` matches exactly one character. Of the
+/// possible matches, the shortest is taken, or the longest if `greedy`. Empty matches don't count.
+pub(crate) fn glob_match_len(pattern: &str, content: &str, greedy: bool) -> Option<usize> {
+    if pattern.is_empty() {
+        return None;
+    }
+    let mut ends: Vec<usize> = vec![0];
+    for token in pattern.chars() {
+        let next: Vec<usize> = match token {
+            '*' => (ends[0]..=content.len()).filter(|index| content.is_char_boundary(*index)).collect(),
+            '?' => ends.iter()
+                .filter_map(|end| content[*end..].chars().next().map(|character| end + character.len_utf8()))
+                .collect(),
+            literal => ends.iter()
+                .filter(|end| content[**end..].starts_with(literal))
+                .map(|end| end + literal.len_utf8())
+                .collect(),
+        };
+        if next.is_empty() {
+            return None;
+        }
+        ends = next;
+    }
+    if greedy {
+        ends.last().copied().filter(|len| *len > 0)
+    } else {
+        ends.into_iter().find(|len| *len > 0)
+    }
+}
+
+/// Gets first range of given query in given content, where any run of whitespace in the query
+/// matches any (non-empty) run of whitespace in the content. Leading and trailing whitespace in the
+/// query is ignored, and a query without any words never matches.
+pub(crate) fn get_range_flexible_ws(query: &str, content: &str) -> Option<Range<usize>> {
+    let mut words = query.split_whitespace();
+    let first = words.next()?;
+    let mut offset: usize = 0;
+    while let Some(found) = content[offset..].find(first) {
+        let start = offset + found;
+        let mut end = start + first.len();
+        let matched = words.clone().all(|word| {
+            let rest = &content[end..];
+            let after_whitespace = rest.trim_start();
+            if after_whitespace.len() == rest.len() || !after_whitespace.starts_with(word) {
+                return false;
+            }
+            end += rest.len() - after_whitespace.len() + word.len();
+            true
+        });
+        if matched {
+            return Some(start..end);
+        }
+        offset = start + content[start..].chars().next().map_or(1, char::len_utf8);
+    }
+    None
+}
+
+/// Gets first range of given query in given content, where a single `optional` char may appear in
+/// the content between any pair of query chars, such as a hyphen in `"e-mail"` for `"email"`. The
+/// range covers the actual content matched. An empty query never matches.
+pub(crate) fn get_range_tolerating(query: &str, content: &str, optional: char) -> Option<Range<usize>> {
+    content.char_indices().find_map(|(start, _)| {
+        match_len_tolerating(query, &content[start..], optional).map(|len| start..(start + len))
+    })
+}
+
+/// Private, gets the byte length of the match of the query at the start of the content, allowing a
+/// single `optional` char between any pair of query chars.
+fn match_len_tolerating(query: &str, content: &str, optional: char) -> Option<usize> {
+    let mut chars = query.chars();
+    let first = chars.next()?;
+    if !content.starts_with(first) {
+        return None;
+    }
+    let mut len = first.len_utf8();
+    for character in chars {
+        let rest = &content[len..];
+        if rest.starts_with(character) {
+            len += character.len_utf8();
+        } else if rest.starts_with(optional) && rest[optional.len_utf8()..].starts_with(character) {
+            len += optional.len_utf8() + character.len_utf8();
+        } else {
+            return None;
+        }
+    }
+    Some(len)
+}
+
+/// Gets first range of given query in given content, ignoring ASCII case (non-ASCII characters
+/// must match exactly). An empty query never matches.
+pub(crate) fn get_range_ignore_ascii_case(query: &str, content: &str) -> Option<Range<usize>> {
+    if query.is_empty() {
+        return None;
+    }
+    content
+        .char_indices()
+        .map(|(start, _)| start..(start + query.len()))
+        .take_while(|range| range.end <= content.len())
+        .find(|range| {
+            content.is_char_boundary(range.end)
+                && content.as_bytes()[range.clone()].eq_ignore_ascii_case(query.as_bytes())
+        })
+}
+
+/// Gets the start of every occurrence of the query in the content, including overlapping ones, in
+/// ascending order.
+fn all_starts(query: &str, content: &str) -> Vec<usize> {
+    let mut starts: Vec<usize> = Vec::new();
+    let mut offset: usize = 0;
+    while let Some(range) = get_range(query, &content[offset..]) {
+        let start = offset + range.start;
+        starts.push(start);
+        offset = start + content[start..].chars().next().map_or(1, char::len_utf8);
+    }
+    starts
+}
+
+/// Converts a single word to title case, capitalizing the first character and lower-casing the
+/// rest, working on chars so that a multibyte first character is handled.
+pub(crate) fn title_case_word(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+/// Private, appends the template to the result with each `$n` group reference replaced by the
+/// captured text and each `$$` by a literal `$`.
+#[cfg(feature = "regex")]
+fn expand_template(template: &str, captures: &Captures, result: &mut String) {
+    let mut rest = template;
+    while let Some(index) = rest.find('$') {
+        result.push_str(&rest[..index]);
+        rest = &rest[(index + 1)..];
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if rest.starts_with('$') {
+            result.push('$');
+            rest = &rest[1..];
+        } else if digits > 0 {
+            let group = rest[..digits].parse::<usize>().ok().and_then(|group| captures.get(group));
+            if let Some(group) = group {
+                result.push_str(group.as_str());
+            }
+            rest = &rest[digits..];
+        } else {
+            result.push('$');
+        }
+    }
+    result.push_str(rest);
+}
+
+/// Private, rebuilds the content with each whitespace-separated word mapped by the closure,
+/// keeping the whitespace as is.
+fn map_words<F>(content: &str, map: F) -> String where F: Fn(&str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut word_start: Option<usize> = None;
+    for (index, character) in content.char_indices() {
+        if character.is_whitespace() {
+            if let Some(start) = word_start.take() {
+                result.push_str(&map(&content[start..index]));
+            }
+            result.push(character);
+        } else if word_start.is_none() {
+            word_start = Some(index);
+        }
+    }
+    if let Some(start) = word_start {
+        result.push_str(&map(&content[start..]));
+    }
+    result
+}
+
+/// Private, splits the content into lower-cased words for case conversion, on whitespace,
+/// underscores, hyphens and camel case humps. A hump is an uppercase char after a lowercase char
+/// or digit, or the last of a run of uppercase chars when a lowercase char follows it.
+fn case_words(content: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let chunks = content
+        .split(|c: char| c.is_whitespace() || c == '_' || c == '-')
+        .filter(|chunk| !chunk.is_empty());
+    for chunk in chunks {
+        let chars: Vec<char> = chunk.chars().collect();
+        let mut word = String::new();
+        for (index, character) in chars.iter().enumerate() {
+            let is_hump = index > 0 && character.is_uppercase() && {
+                let previous = chars[index - 1];
+                let next_is_lower = chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+                previous.is_lowercase() || previous.is_numeric() || (previous.is_uppercase() && next_is_lower)
+            };
+            if is_hump {
+                words.push(word);
+                word = String::new();
+            }
+            word.extend(character.to_lowercase());
+        }
+        words.push(word);
+    }
+    words
+}
+
+/// Checks if a word is an acronym: two or more characters, at least one letter and no lowercase.
+fn is_acronym(word: &str) -> bool {
+    word.chars().count() >= 2
+        && word.chars().any(char::is_alphabetic)
+        && !word.chars().any(char::is_lowercase)
+}
+
+/// Computes the Levenshtein edit distance (insertions, deletions and substitutions of characters)
+/// between two strings.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut current: Vec<usize> = vec![0; b_chars.len() + 1];
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let substitution = previous[j] + if a_char == *b_char { 0 } else { 1 };
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b_chars.len()]
+}
+
+/// Expands a range by up to `radius` characters on each side, clamped to the content. The expanded
+/// range always falls on char boundaries.
+pub(crate) fn expand_by_chars(content: &str, range: &Range<usize>, radius: usize) -> Range<usize> {
+    let start = content[..range.start]
+        .char_indices()
+        .rev()
+        .take(radius)
+        .last()
+        .map_or(range.start, |(index, _)| index);
+    let end = content[range.end..]
+        .char_indices()
+        .nth(radius)
+        .map_or(content.len(), |(index, _)| range.end + index);
+    start..end
+}
+
+/// Escapes the HTML special characters `&`, `<`, `>`, `"` and `'` in the content.
+pub(crate) fn escape_html(content: &str) -> String {
+    let mut escaped = String::with_capacity(content.len());
+    for character in content.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+/// Checks if both sides of a range in the content are word boundaries.
+pub(crate) fn is_whole_word(content: &str, range: &Range<usize>) -> bool {
+    is_word_boundary_before(content, range.start) && is_word_boundary_after(content, range.end)
+}
+
+// Tests ----------------------------------------------------------------------------------------- /
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn can_convert_to_title_case() {
+        assert_eq!(to_title_case("fooBarBaz"), "Foobarbaz");
+        assert_eq!(to_title_case("f"), "F");
+        assert_eq!(to_title_case(""), "");
+        assert_eq!(to_title_case("élan vital"), "Élan vital");
+    }
+
+    #[test]
+    fn can_convert_to_snake_case() {
+        assert_eq!(to_snake_case("fooBarBaz"), "foo_bar_baz");
+        assert_eq!(to_snake_case("foo  bar__baz--qux"), "foo_bar_baz_qux");
+        assert_eq!(to_snake_case("HTTPServer id"), "http_server_id");
+        assert_eq!(to_snake_case("NASA"), "nasa");
+        assert_eq!(to_snake_case(""), "");
+    }
+
+    #[test]
+    fn can_convert_to_kebab_case() {
+        assert_eq!(to_kebab_case("fooBarBaz"), "foo-bar-baz");
+        assert_eq!(to_kebab_case("_foo__bar_"), "foo-bar");
+        assert_eq!(to_kebab_case("getHTTPResponse2XX"), "get-http-response2-xx");
+        assert_eq!(to_kebab_case(""), "");
+    }
+
+    #[test]
+    fn can_convert_to_camel_case() {
+        assert_eq!(to_camel_case("foo_bar_baz"), "fooBarBaz");
+        assert_eq!(to_camel_case("Foo  Bar--baz"), "fooBarBaz");
+        assert_eq!(to_camel_case("HTTP_SERVER"), "httpServer");
+        assert_eq!(to_camel_case("élan vital"), "élanVital");
+        assert_eq!(to_camel_case(""), "");
+    }
+
+    #[test]
+    fn case_conversions_compose_with_transforms() {
+        let result = QueryRangeItr::transform_query("myVar", "let myVar = 1;", to_snake_case);
+        assert_eq!(result, "let my_var = 1;");
+    }
+
+    #[test]
+    fn can_convert_each_word_to_title_case() {
+        assert_eq!(to_title_case_words("fooBar baz"), "Foobar Baz");
+        assert_eq!(to_title_case_words("  the QUICK\tfox "), "  The Quick\tFox ");
+        assert_eq!(to_title_case_words("élan vital"), "Élan Vital");
+        assert_eq!(to_title_case_words(""), "");
+    }
+
+    #[test]
+    fn title_case_preserving_acronyms_converts_lowercase_words() {
+        assert_eq!(to_title_case_preserve_acronyms("nasa data"), "Nasa Data");
+        assert_eq!(to_title_case_preserve_acronyms("mIxEd élan"), "Mixed Élan");
+        assert_eq!(to_title_case_preserve_acronyms(""), "");
+    }
+
+    #[test]
+    fn title_case_preserving_acronyms_keeps_acronyms() {
+        assert_eq!(to_title_case_preserve_acronyms("NASA report"), "NASA Report");
+        assert_eq!(to_title_case_preserve_acronyms(" the USA  and A UK-EU deal "), " The USA  And A UK-EU Deal ");
+        assert_eq!(to_title_case_preserve_acronyms("R2D2 42"), "R2D2 42");
+    }
+
+    #[test]
+    fn can_toggle_case() {
+        assert_eq!(toggle_case("Hello"), "hELLO");
+        assert_eq!(toggle_case("fooBarBaz"), "FOObARbAZ");
+        assert_eq!(toggle_case("Élan ÉTÉ"), "éLAN été");
+        assert_eq!(toggle_case(""), "");
+    }
+
+    #[test]
+    fn toggle_case_leaves_non_cased_characters() {
+        assert_eq!(toggle_case("abc-123, DEF!"), "ABC-123, def!");
+    }
+
+    #[test]
+    fn can_get_last_range() {
+        assert_eq!(get_last_range("needle", "needlehaystackneedlehay"), Some(14..20));
+        assert_eq!(get_last_range("é", "éaéa"), Some(3..5));
+        assert_eq!(get_last_range("aa", "aaaa"), Some(2..4));
+    }
+
+    #[test]
+    fn last_range_is_none_without_match() {
+        assert_eq!(get_last_range("pin", "haystack"), None);
+        assert_eq!(get_last_range("pin", ""), None);
+    }
+
+    #[test]
+    fn all_ranges_equal_iterator_ranges() {
+        let cases = [("needle", "haystackneedlehaystackneedle"), ("aa", "aaaaa"), ("é", "éaé")];
+        for (query, content) in cases.iter() {
+            let expected: Vec<Range<usize>> = QueryRangeItr::new(query, content).collect();
+            assert_eq!(get_all_ranges(query, content), expected);
+        }
+    }
+
+    #[test]
+    fn all_ranges_are_empty_without_match() {
+        assert!(get_all_ranges("pin", "haystack").is_empty());
+        assert!(get_all_ranges("pin", "").is_empty());
+    }
+
+    #[test]
+    fn can_get_byte_range() {
+        assert_eq!(get_byte_range(b"\x00\x01", b"\x00\x00\x01\xff"), Some(1..3));
+        assert_eq!(get_byte_range(b"\xff", b"\x00\xff"), Some(1..2));
+        assert_eq!(get_byte_range(b"\x01\x02", b"\x00\x01"), None);
+        assert_eq!(get_byte_range(b"", b"\x00\x01"), None);
+    }
+
+    #[test]
+    fn can_search_large_byte_buffer() {
+        let mut haystack = vec![0xA5u8; 1 << 20];
+        let offsets = [0, 4096, 500_000, (1 << 20) - 3];
+        for offset in offsets.iter() {
+            haystack[*offset..(*offset + 3)].copy_from_slice(b"\xde\xad\xbe");
+        }
+        let ranges: Vec<Range<usize>> = search_bytes(b"\xde\xad\xbe", &haystack).collect();
+        let expected: Vec<Range<usize>> = offsets.iter().map(|offset| *offset..(offset + 3)).collect();
+        assert_eq!(ranges, expected);
+    }
+
+    #[test]
+    fn can_trim_leading_whitespace_from_range() {
+        assert_eq!(trim_range(" \tneedle", 0..8), 2..8);
+    }
+
+    #[test]
+    fn can_trim_trailing_whitespace_from_range() {
+        assert_eq!(trim_range("needle \n", 0..8), 0..6);
+    }
+
+    #[test]
+    fn can_trim_whitespace_from_both_ends_of_range() {
+        let content = "x  café  x";
+        let range = trim_range(content, 1..10);
+        assert_eq!(&content[range], "café");
+    }
+
+    #[test]
+    fn all_whitespace_range_collapses_to_start() {
+        assert_eq!(trim_range("a    b", 1..5), 1..1);
+        assert_eq!(trim_range("ab", 1..1), 1..1);
+    }
+
+    #[test]
+    fn can_snap_mid_codepoint_offsets() {
+        let content = "a🦀b";
+        for offset in 2..5 {
+            assert_eq!(snap_boundary_down(content, offset), 1);
+            assert_eq!(snap_boundary_up(content, offset), 5);
+        }
+        assert_eq!(snap_boundary_down(content, 0), 0);
+        assert_eq!(snap_boundary_up(content, 1), 1);
+        assert_eq!(snap_boundary_down(content, 99), content.len());
+    }
+
+    #[test]
+    fn can_validate_sorted_disjoint_ranges() {
+        assert_eq!(validate_ranges(&[]), Ok(()));
+        assert_eq!(validate_ranges(&[0..2, 2..2, 5..9]), Ok(()));
+    }
+
+    #[test]
+    fn validate_ranges_rejects_unsorted_ranges() {
+        assert_eq!(validate_ranges(&[0..1, 5..6, 2..3]), Err(RangeError::Unsorted(2)));
+    }
+
+    #[test]
+    fn validate_ranges_rejects_overlapping_ranges() {
+        assert_eq!(validate_ranges(&[0..4, 3..6]), Err(RangeError::Overlapping(1)));
+        assert_eq!(validate_ranges(&[0..4, 0..1]), Err(RangeError::Overlapping(1)));
+    }
+
+    #[test]
+    fn validate_ranges_rejects_inverted_ranges() {
+        assert_eq!(validate_ranges(&[0..1, Range { start: 4, end: 2 }]), Err(RangeError::Inverted(1)));
+        assert_eq!(RangeError::Inverted(1).to_string(), "range 1 has a start greater than its end");
+    }
+
+    #[test]
+    fn merge_ranges_keeps_disjoint_ranges() {
+        assert_eq!(merge_ranges(vec![5..7, 0..2]), vec![0..2, 5..7]);
+    }
+
+    #[test]
+    fn merge_ranges_collapses_contained_ranges() {
+        assert_eq!(merge_ranges(vec![0..10, 2..4, 6..8]), vec![0..10]);
+    }
+
+    #[test]
+    fn merge_ranges_of_nothing_is_empty() {
+        assert!(merge_ranges(Vec::<Range<usize>>::new()).is_empty());
+    }
+
+    #[test]
+    fn merge_ranges_joins_touching_ranges() {
+        assert_eq!(merge_ranges(vec![3u8..5, 0..3]), vec![0..5]);
+    }
+
+    #[test]
+    fn can_reassemble_segments() {
+        let content = "haystackneedlehay";
+        let segments = vec![Segment::Other(0..8), Segment::Match(8..14), Segment::Other(14..17)];
+        assert_eq!(reassemble(content, &segments), content);
+        let reordered = vec![segments[1].clone(), segments[2].clone(), segments[0].clone()];
+        assert_eq!(reassemble(content, &reordered), "needlehayhaystack");
+    }
+
+    #[test]
+    fn can_get_range_tolerating_optional_char() {
+        assert_eq!(get_range_tolerating("email", "my e-mail", '-'), Some(3..9));
+        assert_eq!(get_range_tolerating("email", "my email", '-'), Some(3..8));
+        assert_eq!(get_range_tolerating("email", "e--mail", '-'), None);
+        assert_eq!(get_range_tolerating("email", "-email", '-'), Some(1..6));
+        assert_eq!(get_range_tolerating("", "email", '-'), None);
+    }
+
+    #[test]
+    fn can_get_range_ignoring_ascii_case() {
+        assert_eq!(get_range_ignore_ascii_case("needle", "hayNEEDLEhay"), Some(3..9));
+        assert_eq!(get_range_ignore_ascii_case("é", "aÉé"), Some(3..5));
+        assert_eq!(get_range_ignore_ascii_case("", "abc"), None);
+        assert_eq!(get_range_ignore_ascii_case("abcd", "abc"), None);
+    }
+
+    #[test]
+    fn can_search_lines() {
+        let lines = vec!["haystackneedle", "haystack", "needleneedle", ""];
+        let matches: Vec<(usize, Range<usize>)> = search_lines("needle", &lines).collect();
+        assert_eq!(matches, vec![(0, 8..14), (2, 0..6), (2, 6..12)]);
+    }
+
+    #[test]
+    fn search_lines_does_not_match_across_lines() {
+        let lines = vec!["haystacknee", "dlehaystack"];
+        assert_eq!(search_lines("needle", &lines).count(), 0);
+    }
+
+    #[test]
+    fn can_search_char_stream_one_char_at_a_time() {
+        let mut source = "ab☕needlecd needle".chars();
+        let stream = std::iter::from_fn(move || source.next());
+        let ranges: Vec<Range<usize>> = search_char_stream("needle", stream).collect();
+        assert_eq!(ranges, vec![3..9, 12..18]);
+    }
+
+    #[test]
+    fn char_stream_matches_do_not_overlap() {
+        let ranges: Vec<Range<usize>> = search_char_stream("aa", "aaaaa".chars()).collect();
+        assert_eq!(ranges, vec![0..2, 2..4]);
+        assert_eq!(search_char_stream("", "aaa".chars()).count(), 0);
+        assert_eq!(search_char_stream("needle", "need".chars()).count(), 0);
+    }
+
+    #[test]
+    fn nearest_match_can_be_before_offset() {
+        let content = "needlehaystackhaystackneedle";
+        assert_eq!(nearest_match("needle", content, 9), Some(0..6));
+    }
+
+    #[test]
+    fn nearest_match_can_be_after_offset() {
+        let content = "needlehaystackhaystackneedle";
+        assert_eq!(nearest_match("needle", content, 18), Some(22..28));
+    }
+
+    #[test]
+    fn nearest_match_can_be_at_offset() {
+        let content = "needlehaystackneedlehaystack";
+        assert_eq!(nearest_match("needle", content, 14), Some(14..20));
+    }
+
+    #[test]
+    fn nearest_match_tie_prefers_match_after_offset() {
+        let content = "needle----needle";
+        assert_eq!(nearest_match("needle", content, 5), Some(10..16));
+        assert_eq!(nearest_match("needle", "haystack", 5), None);
+    }
+
+    #[test]
+    fn most_centered_match_prefers_central_match() {
+        let content = "needlehaystackhaystackneedlehaystackhayneedle";
+        assert_eq!(most_centered_match("needle", content), Some(22..28));
+        let content = "xneedlehaystackhaystackneedle";
+        assert_eq!(most_centered_match("needle", content), Some(1..7));
+    }
+
+    #[test]
+    fn most_centered_match_ties_go_to_earlier_match() {
+        assert_eq!(most_centered_match("ab", "xabxxabx"), Some(1..3));
+        assert_eq!(most_centered_match("needle", "haystack"), None);
+    }
+
+    #[test]
+    fn min_window_finds_smallest_window_in_any_order() {
+        let content = "cat ... dog ... bird cat dog";
+        assert_eq!(min_window(&["dog", "cat", "bird"], content), Some(16..28));
+        assert_eq!(min_window(&["cat", "dog"], content), Some(21..28));
+        let content = "dog cat";
+        assert_eq!(min_window(&["cat", "dog"], content), Some(0..7));
+    }
+
+    #[test]
+    fn min_window_handles_single_and_overlapping_queries() {
+        let content = "xx needle yy needle";
+        assert_eq!(min_window(&["needle"], content), Some(3..9));
+        assert_eq!(min_window(&["need", "needle", "dle"], content), Some(3..9));
+    }
+
+    #[test]
+    fn min_window_is_none_when_a_query_is_absent() {
+        assert_eq!(min_window(&["cat", "cow"], "cat dog"), None);
+        assert_eq!(min_window(&["cat", ""], "cat dog"), None);
+        assert_eq!(min_window(&[], "cat dog"), None);
+    }
+
+    #[test]
+    fn after_last_with_multiple_matches() {
+        assert_eq!(after_last(".", "a.b.c"), "c");
+        assert_eq!(after_last(".", "archive.tar.gz"), "gz");
+        assert_eq!(after_last(".", "trailing."), "");
+    }
+
+    #[test]
+    fn after_last_without_match_is_whole_content() {
+        assert_eq!(after_last(".", "abc"), "abc");
+        assert_eq!(after_last("", "abc"), "abc");
+    }
+
+    #[test]
+    fn before_first_with_multiple_matches() {
+        assert_eq!(before_first(".", "a.b.c"), "a");
+        assert_eq!(before_first(".", ".hidden"), "");
+    }
+
+    #[test]
+    fn before_first_without_match_is_whole_content() {
+        assert_eq!(before_first(".", "abc"), "abc");
+        assert_eq!(before_first("", "abc"), "abc");
+    }
+
+    #[test]
+    fn relevance_is_higher_with_more_matches() {
+        let one = relevance("needle", "haystackneedlehaystackhaystack");
+        let two = relevance("needle", "haystackneedlehaystackneedle");
+        let three = relevance("needle", "needleneedlehaystackneedle");
+        assert!(one < two);
+        assert!(two < three);
+    }
+
+    #[test]
+    fn relevance_is_normalized() {
+        assert_eq!(relevance("needle", "haystack"), 0.0);
+        assert_eq!(relevance("needle", ""), 0.0);
+        let score = relevance("needle", "needlehaystack");
+        assert!(score > 0.0 && score < 1.0);
+    }
+
+    #[test]
+    fn relevance_prefers_dense_over_sparse_content() {
+        let dense = relevance("needle", "needlehaystack");
+        let sparse = format!("{}{}", "needle".repeat(100), "x".repeat(100_000));
+        let sparse = relevance("needle", &sparse);
+        assert!(dense > sparse);
+        assert!(sparse < 0.05);
+    }
+
+    #[test]
+    fn relevance_of_exact_match_is_one() {
+        assert_eq!(relevance("a", "a"), 1.0);
+        assert_eq!(relevance("needle", "needleneedleneedle"), 1.0);
+    }
+
+    #[test]
+    fn zero_width_range_is_within_any_content() {
+        assert!(is_within("", &(0..0)));
+        assert!(is_within("012345", &(0..0)));
+        assert!(is_within("012345", &(6..6)));
+    }
+
+    #[test]
+    fn is_within_strict_rejects_zero_width_range() {
+        assert!(!is_within_strict("", &(0..0)));
+        assert!(!is_within_strict("012345", &(0..0)));
+        assert!(!is_within_strict("012345", &(3..3)));
+    }
+
+    #[test]
+    fn is_within_strict_checks_bounds() {
+        assert!(is_within_strict("012345", &(0..2)));
+        assert!(is_within_strict("012345", &(5..6)));
+        assert!(!is_within_strict("012345", &(2..7)));
+        assert!(!is_within_strict("", &(0..1)));
+    }
+
+    #[test]
+    fn is_match_at_is_true_for_correct_range() {
+        let content = "haystackneedlehaystack";
+        assert!(is_match_at("needle", content, &(8..14)));
+    }
+
+    #[test]
+    fn is_match_at_is_false_for_shifted_range() {
+        let content = "haystackneedlehaystack";
+        assert!(!is_match_at("needle", content, &(7..13)));
+        assert!(!is_match_at("needle", content, &(9..15)));
+    }
+
+    #[test]
+    fn is_match_at_is_false_for_out_of_bounds_range() {
+        let content = "haystackneedle";
+        assert!(!is_match_at("needle", content, &(12..18)));
+        assert!(!is_match_at("needle", content, &(20..26)));
+        assert!(!is_match_at("é", "aé", &(0..1)));
+    }
+
+    #[test]
+    fn can_apply_replacements_in_order() {
+        let content = "haystackneedlehaystackneedle";
+        let replacements = vec![(8..14, String::from("N")), (22..28, String::from("M"))];
+        assert_eq!(apply_replacements(content, replacements), "haystackNhaystackM");
+    }
+
+    #[test]
+    fn can_apply_replacements_out_of_order() {
+        let content = "haystackneedlehaystackneedle";
+        let replacements = vec![(22..28, String::from("M")), (0..0, String::from(">")), (8..14, String::from("N"))];
+        assert_eq!(apply_replacements(content, replacements), ">haystackNhaystackM");
+    }
+
+    #[test]
+    fn apply_replacements_skips_overlapping_and_invalid_ranges() {
+        let content = "abcdef";
+        let replacements = vec![(1..4, String::from("X")), (2..5, String::from("Y")), (4..9, String::from("Z"))];
+        assert_eq!(apply_replacements(content, replacements), "aXef");
+        assert_eq!(apply_replacements(content, Vec::new()), "abcdef");
+    }
+
+    #[test]
+    fn can_compute_edit_distance() {
+        assert_eq!(edit_distance("needle", "needle"), 0);
+        assert_eq!(edit_distance("needle", "neddle"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("café", "cafe"), 1);
+    }
+
+    #[test]
+    fn can_expand_range_by_chars() {
+        let content = "aé☕needle☕éa";
+        assert_eq!(expand_by_chars(content, &(6..12), 2), 1..17);
+        assert_eq!(expand_by_chars(content, &(6..12), 10), 0..content.len());
+        assert_eq!(expand_by_chars(content, &(6..12), 0), 6..12);
+    }
+
+    #[test]
+    fn can_replace_many() {
+        let mut map = HashMap::new();
+        map.insert("{name}", "Ann");
+        map.insert("{day}", "Friday");
+        assert_eq!(replace_many("Hi {name}, see you {day}. Bye {name}!", &map), "Hi Ann, see you Friday. Bye Ann!");
+        assert_eq!(replace_many("nothing here", &map), "nothing here");
+    }
+
+    #[test]
+    fn replace_many_prefers_longest_key() {
+        let mut map = HashMap::new();
+        map.insert("foo", "1");
+        map.insert("foobar", "2");
+        map.insert("bar", "3");
+        assert_eq!(replace_many("foobar foo barfoo", &map), "2 1 31");
+    }
+
+    #[test]
+    fn replace_many_does_not_rescan_replacements() {
+        let mut map = HashMap::new();
+        map.insert("a", "b");
+        map.insert("b", "a");
+        map.insert("", "x");
+        assert_eq!(replace_many("abba", &map), "baab");
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn can_replace_regex_with_one_group() {
+        let pattern = Regex::new(r"(\d+)px").unwrap();
+        assert_eq!(replace_all_regex(&pattern, "10px by 20px", "${1}em"), "${1}em by ${1}em");
+        assert_eq!(replace_all_regex(&pattern, "10px by 20px", "$1em"), "10em by 20em");
+        assert_eq!(replace_all_regex(&pattern, "no sizes", "$1em"), "no sizes");
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn can_replace_regex_with_two_groups() {
+        let pattern = Regex::new(r"(\w+)=(\w+)").unwrap();
+        assert_eq!(replace_all_regex(&pattern, "a=1, b=2", "$2=$1"), "1=a, 2=b");
+        assert_eq!(replace_all_regex(&pattern, "a=1", "[$0]"), "[a=1]");
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn replace_regex_handles_escapes_and_missing_groups() {
+        let pattern = Regex::new(r"(a)|(b)").unwrap();
+        assert_eq!(replace_all_regex(&pattern, "ab", "<$1|$2>"), "<a|><|b>");
+        assert_eq!(replace_all_regex(&pattern, "a", "$$1 $9 $x $"), "$1  $x $");
+        assert_eq!(replace_all_regex(&pattern, "a", "$99999999999999999999"), "");
+    }
+
+    #[test]
+    fn repeat_matcher_matches_two_to_three_repeats() {
+        let matcher = repeat_matcher("ab", 2, 3);
+        assert_eq!(matcher("abab"), Some(4));
+        assert_eq!(matcher("ababab"), Some(6));
+        assert_eq!(matcher("abababab"), Some(6));
+        assert_eq!(matcher("ab"), None);
+        assert_eq!(matcher("xabab"), None);
+    }
+
+    #[test]
+    fn repeat_matcher_never_matches_empty() {
+        assert_eq!(repeat_matcher("", 0, 3)("abc"), None);
+        assert_eq!(repeat_matcher("ab", 0, 3)("xyz"), None);
+    }
+
+    #[test]
+    fn can_find_ascii_char_ranges() {
+        let ranges: Vec<Range<usize>> = find_char_ranges("a,b;c d", &[',', ';', ' ']).collect();
+        assert_eq!(ranges, vec![1..2, 3..4, 5..6]);
+        assert_eq!(find_char_ranges("abc", &[',']).count(), 0);
+    }
+
+    #[test]
+    fn can_find_multibyte_char_ranges() {
+        let content = "a→b,c☕d";
+        let ranges: Vec<Range<usize>> = find_char_ranges(content, &['→', ',', '☕']).collect();
+        assert_eq!(ranges, vec![1..4, 5..6, 7..10]);
+        let delimiters: Vec<&str> = ranges.into_iter().map(|range| &content[range]).collect();
+        assert_eq!(delimiters, vec!["→", ",", "☕"]);
+    }
+
+    #[test]
+    fn is_tiling_with_exact_tiling() {
+        assert!(is_tiling("ab", "abab"));
+        assert!(is_tiling("ab", "ab"));
+        assert!(is_tiling("☕", "☕☕☕"));
+    }
+
+    #[test]
+    fn is_tiling_with_partial_tiling() {
+        assert!(!is_tiling("ab", "aba"));
+        assert!(!is_tiling("ab", "abba"));
+        assert!(!is_tiling("abc", "ab"));
+    }
+
+    #[test]
+    fn is_tiling_with_empty_unit_or_content() {
+        assert!(!is_tiling("", "abab"));
+        assert!(!is_tiling("", ""));
+        assert!(!is_tiling("ab", ""));
+    }
+
+    #[test]
+    fn tiling_distance_is_zero_for_perfect_tiling() {
+        assert_eq!(tiling_distance("ab", "abab"), 0);
+        assert_eq!(tiling_distance("☕x", "☕x☕x"), 0);
+    }
+
+    #[test]
+    fn tiling_distance_counts_edits_for_near_tilings() {
+        assert_eq!(tiling_distance("ab", "abxb"), 1);
+        assert_eq!(tiling_distance("ab", "baba"), 4);
+        assert_eq!(tiling_distance("abc", "abcab"), 1);
+        assert_eq!(tiling_distance("abc", "abca"), 1);
+        assert_eq!(tiling_distance("abc", "a"), 2);
+    }
+
+    #[test]
+    fn tiling_distance_with_empty_unit_or_content() {
+        assert_eq!(tiling_distance("abc", ""), 3);
+        assert_eq!(tiling_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn can_check_for_whole_word() {
+        let content = "cat concatenate élancat";
+        assert!(is_whole_word(content, &(0..3)));
+        assert!(!is_whole_word(content, &(7..10)));
+        assert!(!is_whole_word(content, &(22..25)));
+    }
+
+    #[test]
+    fn can_apply_a_shift_to_a_number() {
+        let number = 5;
+        let shift = Shift::Up(2);
+        assert_eq!(shift.apply_to_number(number), Some(7));
+        let number = 8;
+        let shift = Shift::Down(3);
+        assert_eq!(shift.apply_to_number(number), Some(5));
+    }
+
+    #[test]
+    fn can_apply_a_shift_to_a_range() {
         let range = 0..5;
         let shift = Shift::Up(2);
         assert_eq!(shift.apply_to_range(range), Some(2..7));
@@ -171,6 +1894,51 @@ mod test {
         assert_eq!(shift.apply_to_range(range), Some(1..4));
     }
 
+    #[test]
+    fn can_shift_with_fluent_api() {
+        assert_eq!(Shift::Up(2).shift(0..5), Some(2..7));
+        assert_eq!(Shift::Down(3).shift(4..7), Some(1..4));
+        assert_eq!(Shift::Down(3usize).shift(2..7), None);
+        assert_eq!(Shift::Up(1u8).shift(0..255), None);
+    }
+
+    #[test]
+    fn can_shift_with_operators() {
+        assert_eq!((0..5) + Shift::Up(2), Some(2..7));
+        assert_eq!((4..7) + Shift::Down(3), Some(1..4));
+        assert_eq!((2..7) - Shift::Up(2), Some(0..5));
+        assert_eq!((2..7) - Shift::Down(2), Some(4..9));
+        assert_eq!((0usize..5) - Shift::Up(2), None);
+    }
+
+    #[test]
+    fn shift_ranges_after_insertion_before_all_ranges() {
+        let mut ranges = vec![2..4, 6..9];
+        shift_ranges_after(&mut ranges, 0, Shift::Up(5));
+        assert_eq!(ranges, vec![7..9, 11..14]);
+    }
+
+    #[test]
+    fn shift_ranges_after_insertion_within_a_range() {
+        let mut ranges = vec![2..4, 6..9, 12..13];
+        shift_ranges_after(&mut ranges, 7, Shift::Up(2));
+        assert_eq!(ranges, vec![2..4, 6..9, 14..15]);
+    }
+
+    #[test]
+    fn shift_ranges_after_insertion_after_all_ranges() {
+        let mut ranges = vec![2..4, 6..9];
+        shift_ranges_after(&mut ranges, 9, Shift::Up(2));
+        assert_eq!(ranges, vec![2..4, 6..9]);
+    }
+
+    #[test]
+    fn shift_ranges_after_leaves_overflowing_ranges() {
+        let mut ranges = vec![2..4, 6..9];
+        shift_ranges_after(&mut ranges, 2, Shift::Down(3));
+        assert_eq!(ranges, vec![2..4, 3..6]);
+    }
+
     #[test]
     fn can_shift_a_range() {
         let range = 0..5;
@@ -179,6 +1947,39 @@ mod test {
         assert_ne!(shift_range(new_range.unwrap(), Shift::Down(3)), Some(0..1));
     }
 
+    #[test]
+    fn can_shift_only_range_start_up() {
+        assert_eq!(shift_range_bounds(2usize..10, Shift::Up(3), Shift::Up(0)), Some(5..10));
+        assert_eq!(shift_range_bounds(2usize..10, Shift::Up(8), Shift::Down(0)), Some(10..10));
+    }
+
+    #[test]
+    fn can_shift_only_range_end_down() {
+        assert_eq!(shift_range_bounds(2usize..10, Shift::Down(0), Shift::Down(4)), Some(2..6));
+    }
+
+    #[test]
+    fn shift_range_bounds_is_none_when_inverted_or_overflowing() {
+        assert_eq!(shift_range_bounds(2usize..10, Shift::Up(5), Shift::Down(5)), None);
+        assert_eq!(shift_range_bounds(2usize..10, Shift::Down(3), Shift::Up(0)), None);
+        assert_eq!(shift_range_bounds(2u8..10, Shift::Up(0), Shift::Up(250)), None);
+    }
+
+    #[test]
+    fn can_shift_range_saturating_down_past_zero() {
+        assert_eq!(shift_range_saturating(0usize..5, Shift::Down(10)), 0..0);
+        assert_eq!(shift_range_saturating(8usize..12, Shift::Down(10)), 0..2);
+        assert_eq!(shift_range(0usize..5, Shift::Down(10)), None);
+    }
+
+    #[test]
+    fn can_shift_range_saturating_up_past_max_value() {
+        let max = u8::MAX;
+        assert_eq!(shift_range_saturating(250u8..253, Shift::Up(10)), max..max);
+        assert_eq!(shift_range_saturating(240u8..250, Shift::Up(10)), 250..max);
+        assert_eq!(shift_range(250u8..253, Shift::Up(10)), None);
+    }
+
     #[test]
     fn is_within_is_true_when_range_is_within() {
         let test_str = "012345";
@@ -192,4 +1993,20 @@ mod test {
         let range = 2..7;
         assert_eq!(is_within(test_str, &range), false);
     }
+
+    #[test]
+    fn is_within_is_false_when_range_is_inverted() {
+        let test_str = "0123";
+        assert!(!is_within(test_str, &Range { start: 3, end: 2 }));
+        assert!(!is_within(test_str, &Range { start: 5, end: 2 }));
+    }
+
+    #[test]
+    fn is_within_is_false_when_range_start_is_out_of_bounds() {
+        let test_str = "0123";
+        assert!(!is_within(test_str, &(100..101)));
+        assert!(!is_within(test_str, &(5..5)));
+        assert!(!is_within(test_str, &(-1..2)));
+        assert!(is_within(test_str, &(4..4)));
+    }
 }
\ No newline at end of file